@@ -0,0 +1,227 @@
+use std::fmt;
+
+/// A keyword ability the engine enforces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Keyword {
+    /// Can only be blocked by creatures with flying or reach.
+    Flying,
+    /// Can block creatures with flying.
+    Reach,
+    /// Can only be blocked by two or more creatures.
+    Menace,
+    /// Deals combat damage before creatures without it.
+    FirstStrike,
+    /// Deals combat damage both before and with creatures without first strike.
+    DoubleStrike,
+    /// Assigns damage beyond what its blockers need to the defending player.
+    Trample,
+    /// Cannot attack.
+    Defender,
+    /// Does not tap to attack.
+    Vigilance,
+    /// Can attack the turn it comes under its controller's control.
+    Haste,
+    /// Any damage it deals to a creature destroys it.
+    Deathtouch,
+    /// Damage it deals also gains its controller that much life.
+    Lifelink,
+    /// Lethal damage does not destroy it.
+    Indestructible,
+}
+impl fmt::Display for Keyword {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Keyword::Flying => "flying",
+            Keyword::Reach => "reach",
+            Keyword::Menace => "menace",
+            Keyword::FirstStrike => "first strike",
+            Keyword::DoubleStrike => "double strike",
+            Keyword::Trample => "trample",
+            Keyword::Defender => "defender",
+            Keyword::Vigilance => "vigilance",
+            Keyword::Haste => "haste",
+            Keyword::Deathtouch => "deathtouch",
+            Keyword::Lifelink => "lifelink",
+            Keyword::Indestructible => "indestructible",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A printed card the simulator knows about.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CardInfo {
+    pub name: &'static str,
+    pub cmc: u64,
+    pub pow: u64,
+    pub tou: u64,
+    /// Its rules text, if that is only keywords.
+    pub keywords: &'static [Keyword],
+    /// Why the card is allowed despite its rules text, if it has any.
+    pub notes: &'static str,
+}
+const fn vanilla(name: &'static str, cmc: u64, pow: u64, tou: u64) -> CardInfo {
+    keyworded(name, cmc, pow, tou, &[])
+}
+const fn keyworded(
+    name: &'static str,
+    cmc: u64,
+    pow: u64,
+    tou: u64,
+    keywords: &'static [Keyword],
+) -> CardInfo {
+    CardInfo {
+        name,
+        cmc,
+        pow,
+        tou,
+        keywords,
+        notes: "",
+    }
+}
+impl CardInfo {
+    pub fn has(&self, keyword: Keyword) -> bool {
+        self.keywords.contains(&keyword)
+    }
+}
+
+/// Every creature that can be put in a deck, cheapest first.
+/// Where several cards share stats, `CreatureCard::try_new` picks
+/// the first vanilla one, or if none is vanilla, the first with keywords
+/// as long as they all have the same keywords.
+/// Left out: Permeating Mass (1/1/3), whose ability is a drawback
+/// wrapped in an upside.
+pub static CARDS: &[CardInfo] = &[
+    vanilla("Memnite", 0, 1, 1),
+    vanilla("Phyrexian Walker", 0, 0, 3),
+    vanilla("Icehide Golem", 1, 2, 2),
+    vanilla("Isamaru, Hound of Konda", 1, 2, 2),
+    vanilla("Disowned Ancestor", 1, 0, 4),
+    vanilla("God-Pharaoh's Faithful", 1, 0, 4),
+    vanilla("Kraken Hatchling", 1, 0, 4),
+    vanilla("Lagonna-Band Trailblazer", 1, 0, 4),
+    vanilla("Merfolk Secretkeeper", 1, 0, 4),
+    vanilla("Sidisi's Faithful", 1, 0, 4),
+    vanilla("Tasseled Dromedary", 1, 0, 4),
+    vanilla("Yoked Ox", 1, 0, 4),
+    keyworded("Suntail Hawk", 1, 1, 1, &[Keyword::Flying]),
+    keyworded("Tundra Wolves", 1, 1, 1, &[Keyword::FirstStrike]),
+    keyworded("Raging Goblin", 1, 1, 1, &[Keyword::Haste]),
+    keyworded("Typhoid Rats", 1, 1, 1, &[Keyword::Deathtouch]),
+    keyworded(
+        "Healer's Hawk",
+        1,
+        1,
+        1,
+        &[Keyword::Flying, Keyword::Lifelink],
+    ),
+    keyworded("Perimeter Captain", 1, 0, 4, &[Keyword::Defender]),
+    keyworded("Steel Wall", 1, 0, 4, &[Keyword::Defender]),
+    keyworded("Wall of Runes", 1, 0, 4, &[Keyword::Defender]),
+    vanilla("Bronzehide Lion", 2, 3, 3),
+    vanilla("Fleecemane Lion", 2, 3, 3),
+    vanilla("Kalonian Tusker", 2, 3, 3),
+    vanilla("Watchwolf", 2, 3, 3),
+    vanilla("Grizzled Leotau", 2, 1, 5),
+    vanilla("Dragon's Eye Savants", 2, 0, 6),
+    keyworded("Storm Crow", 2, 1, 2, &[Keyword::Flying]),
+    keyworded("Canopy Spider", 2, 1, 3, &[Keyword::Reach]),
+    keyworded("Youthful Knight", 2, 2, 1, &[Keyword::FirstStrike]),
+    keyworded("Fencing Ace", 2, 1, 1, &[Keyword::DoubleStrike]),
+    keyworded("Boros Swiftblade", 2, 1, 2, &[Keyword::DoubleStrike]),
+    keyworded(
+        "Skyhunter Skirmisher",
+        2,
+        1,
+        1,
+        &[Keyword::Flying, Keyword::DoubleStrike],
+    ),
+    keyworded(
+        "Vault Skirge",
+        2,
+        1,
+        1,
+        &[Keyword::Flying, Keyword::Lifelink],
+    ),
+    keyworded(
+        "Knight of Meadowgrain",
+        2,
+        2,
+        2,
+        &[Keyword::FirstStrike, Keyword::Lifelink],
+    ),
+    keyworded("Fortified Rampart", 2, 0, 6, &[Keyword::Defender]),
+    keyworded("Wall of Tanglecord", 2, 0, 6, &[Keyword::Defender]),
+    vanilla("Steel Leaf Champion", 3, 5, 4),
+    vanilla("Woolly Thoctar", 3, 5, 4),
+    vanilla("Leatherback Baloth", 3, 4, 5),
+    keyworded("Wind Drake", 3, 2, 2, &[Keyword::Flying]),
+    keyworded("Boggart Brute", 3, 3, 2, &[Keyword::Menace]),
+    keyworded("Wall of Stone", 3, 0, 8, &[Keyword::Defender]),
+    keyworded(
+        "Wall of Denial",
+        3,
+        0,
+        8,
+        &[Keyword::Defender, Keyword::Flying],
+    ),
+    keyworded(
+        "Vampire Nighthawk",
+        3,
+        2,
+        3,
+        &[Keyword::Flying, Keyword::Deathtouch, Keyword::Lifelink],
+    ),
+    keyworded("Darksteel Myr", 3, 0, 1, &[Keyword::Indestructible]),
+    CardInfo {
+        notes: "Was clearly printed with a drawback, \
+                but the drawback is irrelevant in this format.",
+        ..vanilla("Nullhide Ferox", 4, 6, 6)
+    },
+    vanilla("Indomitable Ancients", 4, 2, 10),
+    vanilla("Tree of Ancients", 4, 0, 13),
+    keyworded("Giant Spider", 4, 2, 4, &[Keyword::Reach]),
+    keyworded("Lightning Elemental", 4, 4, 1, &[Keyword::Haste]),
+    keyworded("Tree of Perdition", 4, 0, 13, &[Keyword::Defender]),
+    vanilla("Gigantosaurus", 5, 10, 10),
+    keyworded("Air Elemental", 5, 4, 4, &[Keyword::Flying]),
+    keyworded(
+        "Serra Angel",
+        5,
+        4,
+        4,
+        &[Keyword::Flying, Keyword::Vigilance],
+    ),
+    keyworded(
+        "Sentinel Spider",
+        5,
+        4,
+        4,
+        &[Keyword::Vigilance, Keyword::Reach],
+    ),
+    keyworded("Colossal Dreadmaw", 6, 6, 6, &[Keyword::Trample]),
+    keyworded(
+        "Darksteel Gargoyle",
+        7,
+        3,
+        3,
+        &[Keyword::Flying, Keyword::Indestructible],
+    ),
+    keyworded("Crash of Rhinos", 8, 8, 4, &[Keyword::Trample]),
+    vanilla("Void Winnower", 9, 11, 9),
+    vanilla("Inkwell Leviathan", 9, 7, 11),
+    vanilla("Impervious Greatwurm", 10, 16, 16),
+];
+
+/// Look up a card by name, ignoring case.
+pub fn find_card(name: &str) -> Option<&'static CardInfo> {
+    CARDS
+        .iter()
+        .find(|info| info.name.eq_ignore_ascii_case(name))
+}
+/// All cards with the given cost, power and toughness.
+pub fn cards_with_stats(cmc: u64, pow: u64, tou: u64) -> impl Iterator<Item = &'static CardInfo> {
+    CARDS
+        .iter()
+        .filter(move |info| (info.cmc, info.pow, info.tou) == (cmc, pow, tou))
+}
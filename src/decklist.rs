@@ -0,0 +1,209 @@
+use crate::game_data::{Card, CreatureCard, IllegalCreature};
+use std::error::Error;
+use std::fmt;
+
+const LAND_NAMES: &[&str] = &["Land", "Plains", "Island", "Swamp", "Mountain", "Forest"];
+/// The most cards a decklist may add up to.
+pub const MAX_DECK_SIZE: usize = 10_000;
+
+/// Why a decklist line could not be read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecklistErrorKind {
+    /// The line does not start with a number of copies.
+    MissingCount,
+    /// A number of copies with no card after it.
+    MissingCard,
+    UnknownCard(String),
+    IllegalCreature(IllegalCreature),
+    /// The count takes the deck past `MAX_DECK_SIZE` cards.
+    TooManyCards,
+}
+/// An unreadable line of a decklist, numbered from 1.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecklistError {
+    pub line: usize,
+    pub kind: DecklistErrorKind,
+}
+impl fmt::Display for DecklistError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            DecklistErrorKind::MissingCount => write!(f, "expected a number of copies"),
+            DecklistErrorKind::MissingCard => write!(f, "expected a card after the count"),
+            DecklistErrorKind::UnknownCard(name) => write!(f, "unknown card {}", name),
+            DecklistErrorKind::IllegalCreature(illegal) => write!(f, "{}", illegal),
+            DecklistErrorKind::TooManyCards => {
+                write!(f, "the deck has more than {} cards", MAX_DECK_SIZE)
+            }
+        }
+    }
+}
+impl Error for DecklistError {}
+
+// `cmc/pow/tou`, if the name is three numbers.
+fn parse_stats(name: &str) -> Option<(u64, u64, u64)> {
+    let mut stats = name.split('/').map(|n| n.trim().parse::<u64>());
+    match (stats.next(), stats.next(), stats.next(), stats.next()) {
+        (Some(Ok(cmc)), Some(Ok(pow)), Some(Ok(tou)), None) => Some((cmc, pow, tou)),
+        _ => None,
+    }
+}
+fn parse_card(name: &str) -> Result<Card, DecklistErrorKind> {
+    if LAND_NAMES
+        .iter()
+        .any(|land| land.eq_ignore_ascii_case(name))
+    {
+        return Ok(Card::Land);
+    }
+    if let Some((cmc, pow, tou)) = parse_stats(name) {
+        return CreatureCard::try_new(cmc, pow, tou)
+            .map(Card::Creature)
+            .map_err(DecklistErrorKind::IllegalCreature);
+    }
+    CreatureCard::named(name)
+        .map(Card::Creature)
+        .ok_or_else(|| DecklistErrorKind::UnknownCard(name.to_string()))
+}
+
+/// Read a plain-text decklist with one `<count> <card>` entry per line,
+/// where a card is `Land` (or a basic land name), a creature name
+/// from `cards::CARDS` such as `Watchwolf`, or `cmc/pow/tou` such as `2/3/3`.
+/// Blank lines and lines starting with `#` are ignored.
+pub fn parse_decklist(text: &str) -> Result<Vec<Card>, DecklistError> {
+    let mut deck = vec![];
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let error = |kind| DecklistError {
+            line: index + 1,
+            kind,
+        };
+        let (count, name) = line.split_at(line.find(char::is_whitespace).unwrap_or(line.len()));
+        let count: usize = count
+            .parse()
+            .map_err(|_| error(DecklistErrorKind::MissingCount))?;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(error(DecklistErrorKind::MissingCard));
+        }
+        if count > MAX_DECK_SIZE - deck.len() {
+            return Err(error(DecklistErrorKind::TooManyCards));
+        }
+        let card = parse_card(name).map_err(error)?;
+        deck.extend(std::iter::repeat_n(card, count));
+    }
+    Ok(deck)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_in(text: &str) -> DecklistError {
+        parse_decklist(text).expect_err("Unreadable")
+    }
+
+    #[test]
+    fn line_numbers_count_skipped_lines() {
+        let text = "# Wolves\n\n4 Watchwolf\n  \n# Then\nWatchwolf\n";
+        assert_eq!(
+            error_in(text),
+            DecklistError {
+                line: 6,
+                kind: DecklistErrorKind::MissingCount,
+            }
+        );
+    }
+    #[test]
+    fn count_and_card_are_both_needed() {
+        assert_eq!(error_in("Watchwolf").kind, DecklistErrorKind::MissingCount);
+        assert_eq!(
+            error_in("four Watchwolf").kind,
+            DecklistErrorKind::MissingCount
+        );
+        assert_eq!(error_in("4").kind, DecklistErrorKind::MissingCard);
+        assert_eq!(error_in("4   ").kind, DecklistErrorKind::MissingCard);
+    }
+    #[test]
+    fn counts_stop_at_the_max_deck_size() {
+        assert_eq!(
+            error_in("# Lands\n1000000000000 Land"),
+            DecklistError {
+                line: 2,
+                kind: DecklistErrorKind::TooManyCards,
+            }
+        );
+        assert_eq!(
+            error_in("9999 Land\n2 Memnite").kind,
+            DecklistErrorKind::TooManyCards
+        );
+        assert_eq!(
+            parse_decklist("9999 Land\n1 Memnite").unwrap().len(),
+            MAX_DECK_SIZE
+        );
+    }
+    #[test]
+    fn cards_by_name_ignore_case() {
+        let deck = parse_decklist("2 watchwolf\n1 Forest\n1 LAND").unwrap();
+        let watchwolf = Card::Creature(CreatureCard::expect_named("Watchwolf"));
+        assert_eq!(
+            deck,
+            vec![watchwolf.clone(), watchwolf, Card::Land, Card::Land]
+        );
+        assert_eq!(
+            error_in("1 Grizzly Bears").kind,
+            DecklistErrorKind::UnknownCard("Grizzly Bears".to_string())
+        );
+    }
+    #[test]
+    fn cards_by_stats_prefer_vanilla() {
+        assert_eq!(
+            parse_decklist("1 2/3/3").unwrap(),
+            vec![Card::Creature(CreatureCard::expect_named(
+                "Bronzehide Lion"
+            ))]
+        );
+        assert_eq!(
+            parse_decklist("1 0 / 1 / 1").unwrap(),
+            vec![Card::Creature(CreatureCard::expect_named("Memnite"))]
+        );
+        assert_eq!(
+            parse_decklist("1 1/0/4").unwrap(),
+            vec![Card::Creature(CreatureCard::expect_named(
+                "Disowned Ancestor"
+            ))]
+        );
+        assert_eq!(
+            parse_decklist("1 6/6/6").unwrap(),
+            vec![Card::Creature(CreatureCard::expect_named(
+                "Colossal Dreadmaw"
+            ))]
+        );
+        assert_eq!(
+            error_in("1 9/9/9").kind,
+            DecklistErrorKind::IllegalCreature(IllegalCreature::NoCreature {
+                cmc: 9,
+                pow: 9,
+                tou: 9,
+            })
+        );
+    }
+    #[test]
+    fn cards_by_stats_with_different_keywords_need_a_name() {
+        assert_eq!(
+            error_in("1 1/1/1").kind,
+            DecklistErrorKind::IllegalCreature(IllegalCreature::Ambiguous {
+                cmc: 1,
+                pow: 1,
+                tou: 1,
+            })
+        );
+        let error = error_in("1 3/0/8");
+        assert_eq!(
+            error.to_string(),
+            "line 1: 3/0/8 could be Wall of Stone or Wall of Denial; name the card"
+        );
+    }
+}
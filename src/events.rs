@@ -0,0 +1,379 @@
+use crate::game::{EndReason, GameOutcome, Winner};
+use crate::game_data::{Card, CreatureCard};
+use std::fmt;
+use std::io::{self, Write};
+
+/// What combat damage was dealt to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DamageTarget {
+    Player(usize),
+    Creature { player: usize, index: usize },
+}
+/// Something that happened during a game.
+/// `player` is always a seat index, 0 for the player who went first,
+/// and creature indices are positions on that player's battlefield
+/// at the time of the event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GameEvent {
+    /// `hand` was shuffled back for a muligan.
+    Muligan {
+        player: usize,
+        hand: Vec<Card>,
+    },
+    /// The opening hand after returning cards.
+    Keep {
+        player: usize,
+        hand: Vec<Card>,
+    },
+    TurnStart {
+        player: usize,
+        num_turn: u64,
+    },
+    Draw {
+        player: usize,
+        card: Card,
+    },
+    AttackDeclared {
+        player: usize,
+        attackers: Vec<usize>,
+    },
+    /// Pairs of (blocker, attacker).
+    BlocksDeclared {
+        player: usize,
+        blocks: Vec<(usize, usize)>,
+    },
+    BlockersOrdered {
+        player: usize,
+        attacker: usize,
+        blockers: Vec<usize>,
+    },
+    Damage {
+        player: usize,
+        source: usize,
+        target: DamageTarget,
+        amount: u64,
+    },
+    CreatureDied {
+        player: usize,
+        index: usize,
+        card: CreatureCard,
+    },
+    /// Life from the lifelink of creature `source`.
+    LifeGained {
+        player: usize,
+        source: usize,
+        amount: u64,
+    },
+    LandPlayed {
+        player: usize,
+    },
+    SpellCast {
+        player: usize,
+        card: CreatureCard,
+    },
+    Discard {
+        player: usize,
+        card: Card,
+    },
+    GameEnd {
+        num_turn: u64,
+        outcome: GameOutcome,
+    },
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+fn json_list<T>(items: &[T], to_json: impl Fn(&T) -> String) -> String {
+    let items: Vec<String> = items.iter().map(to_json).collect();
+    format!("[{}]", items.join(","))
+}
+fn creature_card_json(card: &CreatureCard) -> String {
+    format!(
+        "{{\"name\":{},\"cmc\":{},\"pow\":{},\"tou\":{}}}",
+        json_string(card.name()),
+        card.cmc(),
+        card.pow(),
+        card.tou()
+    )
+}
+fn card_json(card: &Card) -> String {
+    match card {
+        Card::Land => json_string("Land"),
+        Card::Creature(creature_card) => creature_card_json(creature_card),
+    }
+}
+fn target_json(target: &DamageTarget) -> String {
+    match target {
+        DamageTarget::Player(player) => format!("{{\"player\":{}}}", player),
+        DamageTarget::Creature { player, index } => {
+            format!("{{\"player\":{},\"creature\":{}}}", player, index)
+        }
+    }
+}
+fn outcome_json(outcome: &GameOutcome) -> String {
+    let reason = match outcome.reason {
+        EndReason::Forfeit(action) => format!(
+            "{},\"illegal_action\":{}",
+            json_string("Forfeit"),
+            json_string(&action.to_string())
+        ),
+        reason => json_string(&format!("{:?}", reason)),
+    };
+    format!(
+        "\"winner\":{},\"reason\":{}",
+        json_string(&format!("{:?}", outcome.winner)),
+        reason
+    )
+}
+
+impl GameEvent {
+    /// A single-line JSON object with an `"event"` field naming the variant.
+    pub fn to_json(&self) -> String {
+        let (name, fields) = match self {
+            GameEvent::Muligan { player, hand } => (
+                "muligan",
+                format!(
+                    "\"player\":{},\"hand\":{}",
+                    player,
+                    json_list(hand, card_json)
+                ),
+            ),
+            GameEvent::Keep { player, hand } => (
+                "keep",
+                format!(
+                    "\"player\":{},\"hand\":{}",
+                    player,
+                    json_list(hand, card_json)
+                ),
+            ),
+            GameEvent::TurnStart { player, num_turn } => (
+                "turn_start",
+                format!("\"player\":{},\"num_turn\":{}", player, num_turn),
+            ),
+            GameEvent::Draw { player, card } => (
+                "draw",
+                format!("\"player\":{},\"card\":{}", player, card_json(card)),
+            ),
+            GameEvent::AttackDeclared { player, attackers } => (
+                "attack_declared",
+                format!(
+                    "\"player\":{},\"attackers\":{}",
+                    player,
+                    json_list(attackers, |a| a.to_string())
+                ),
+            ),
+            GameEvent::BlocksDeclared { player, blocks } => (
+                "blocks_declared",
+                format!(
+                    "\"player\":{},\"blocks\":{}",
+                    player,
+                    json_list(blocks, |(blocker, attacker)| format!(
+                        "{{\"blocker\":{},\"attacker\":{}}}",
+                        blocker, attacker
+                    ))
+                ),
+            ),
+            GameEvent::BlockersOrdered {
+                player,
+                attacker,
+                blockers,
+            } => (
+                "blockers_ordered",
+                format!(
+                    "\"player\":{},\"attacker\":{},\"blockers\":{}",
+                    player,
+                    attacker,
+                    json_list(blockers, |b| b.to_string())
+                ),
+            ),
+            GameEvent::Damage {
+                player,
+                source,
+                target,
+                amount,
+            } => (
+                "damage",
+                format!(
+                    "\"player\":{},\"source\":{},\"target\":{},\"amount\":{}",
+                    player,
+                    source,
+                    target_json(target),
+                    amount
+                ),
+            ),
+            GameEvent::CreatureDied {
+                player,
+                index,
+                card,
+            } => (
+                "creature_died",
+                format!(
+                    "\"player\":{},\"index\":{},\"card\":{}",
+                    player,
+                    index,
+                    creature_card_json(card)
+                ),
+            ),
+            GameEvent::LifeGained {
+                player,
+                source,
+                amount,
+            } => (
+                "life_gained",
+                format!(
+                    "\"player\":{},\"source\":{},\"amount\":{}",
+                    player, source, amount
+                ),
+            ),
+            GameEvent::LandPlayed { player } => ("land_played", format!("\"player\":{}", player)),
+            GameEvent::SpellCast { player, card } => (
+                "spell_cast",
+                format!(
+                    "\"player\":{},\"card\":{}",
+                    player,
+                    creature_card_json(card)
+                ),
+            ),
+            GameEvent::Discard { player, card } => (
+                "discard",
+                format!("\"player\":{},\"card\":{}", player, card_json(card)),
+            ),
+            GameEvent::GameEnd { num_turn, outcome } => (
+                "game_end",
+                format!("\"num_turn\":{},{}", num_turn, outcome_json(outcome)),
+            ),
+        };
+        format!("{{\"event\":{},{}}}", json_string(name), fields)
+    }
+}
+
+fn card_list(cards: &[Card]) -> String {
+    let cards: Vec<String> = cards.iter().map(|card| card.to_string()).collect();
+    cards.join(", ")
+}
+fn index_list(indices: &[usize]) -> String {
+    let indices: Vec<String> = indices.iter().map(|index| index.to_string()).collect();
+    indices.join(", ")
+}
+/// A one-line description for people, with creatures by battlefield index.
+impl fmt::Display for GameEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameEvent::Muligan { player, hand } => {
+                write!(f, "Player {} muligans {}", player, card_list(hand))
+            }
+            GameEvent::Keep { player, hand } => {
+                write!(f, "Player {} keeps {}", player, card_list(hand))
+            }
+            GameEvent::TurnStart { player, num_turn } => {
+                write!(f, "Turn {}, player {}", num_turn, player)
+            }
+            GameEvent::Draw { player, card } => write!(f, "Player {} draws {}", player, card),
+            GameEvent::AttackDeclared { player, attackers } => write!(
+                f,
+                "Player {} attacks with {}",
+                player,
+                index_list(attackers)
+            ),
+            GameEvent::BlocksDeclared { player, blocks } => {
+                let blocks: Vec<String> = blocks
+                    .iter()
+                    .map(|(blocker, attacker)| format!("{} blocks {}", blocker, attacker))
+                    .collect();
+                write!(f, "Player {}: {}", player, blocks.join(", "))
+            }
+            GameEvent::BlockersOrdered {
+                player,
+                attacker,
+                blockers,
+            } => write!(
+                f,
+                "Player {} orders the blockers of {}: {}",
+                player,
+                attacker,
+                index_list(blockers)
+            ),
+            GameEvent::Damage {
+                player,
+                source,
+                target,
+                amount,
+            } => {
+                write!(f, "Player {}'s {} deals {} to ", player, source, amount)?;
+                match target {
+                    DamageTarget::Player(target) => write!(f, "player {}", target),
+                    DamageTarget::Creature { player, index } => {
+                        write!(f, "player {}'s {}", player, index)
+                    }
+                }
+            }
+            GameEvent::CreatureDied {
+                player,
+                index,
+                card,
+            } => write!(f, "Player {}'s {} ({}) dies", player, card, index),
+            GameEvent::LifeGained {
+                player,
+                source,
+                amount,
+            } => write!(
+                f,
+                "Player {}'s {} gains them {} life",
+                player, source, amount
+            ),
+            GameEvent::LandPlayed { player } => write!(f, "Player {} plays a land", player),
+            GameEvent::SpellCast { player, card } => {
+                write!(f, "Player {} casts {}", player, card)
+            }
+            GameEvent::Discard { player, card } => {
+                write!(f, "Player {} discards {}", player, card)
+            }
+            GameEvent::GameEnd { num_turn, outcome } => {
+                write!(f, "Game over on turn {}: ", num_turn)?;
+                match outcome.winner {
+                    Winner::Player1 => write!(f, "player 0 wins by {:?}", outcome.reason),
+                    Winner::Player2 => write!(f, "player 1 wins by {:?}", outcome.reason),
+                    Winner::Draw => write!(f, "draw by {:?}", outcome.reason),
+                }
+            }
+        }
+    }
+}
+
+/// Write one JSON object per line, see `GameEvent::to_json`.
+pub fn write_json_lines<W: Write>(events: &[GameEvent], writer: &mut W) -> io::Result<()> {
+    for event in events {
+        writeln!(writer, "{}", event.to_json())?;
+    }
+    Ok(())
+}
+
+// Collects events only when enabled, so untraced games pay nothing.
+#[derive(Debug, Default)]
+pub(crate) struct EventLog {
+    events: Option<Vec<GameEvent>>,
+}
+impl EventLog {
+    pub(crate) fn enable(&mut self) {
+        self.events.get_or_insert_with(Vec::new);
+    }
+    pub(crate) fn record(&mut self, event: impl FnOnce() -> GameEvent) {
+        if let Some(events) = &mut self.events {
+            events.push(event());
+        }
+    }
+    pub(crate) fn events(&self) -> &[GameEvent] {
+        self.events.as_deref().unwrap_or(&[])
+    }
+}
@@ -0,0 +1,179 @@
+use crate::game_data::Card;
+use std::fmt;
+
+/// Deck construction rules.
+#[derive(Debug, Clone, Copy)]
+pub struct Format {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub min_deck_size: usize,
+    /// Most copies of any one named card; basic lands are exempt.
+    pub max_copies: Option<usize>,
+    /// Names of the creatures allowed; `None` allows all of `cards::CARDS`.
+    pub card_pool: Option<&'static [&'static str]>,
+}
+pub static FORMATS: &[Format] = &[
+    Format {
+        name: "Constructed",
+        description: "At least 60 cards, at most 4 copies of each.",
+        min_deck_size: 60,
+        max_copies: Some(4),
+        card_pool: None,
+    },
+    Format {
+        name: "Limited",
+        description: "At least 40 cards.",
+        min_deck_size: 40,
+        max_copies: None,
+        card_pool: None,
+    },
+    Format {
+        name: "Singleton",
+        description: "At least 60 cards, at most 1 copy of each.",
+        min_deck_size: 60,
+        max_copies: Some(1),
+        card_pool: None,
+    },
+    Format {
+        name: "AnythingGoes",
+        description: "Any deck, as the built-in strategies play.",
+        min_deck_size: 0,
+        max_copies: None,
+        card_pool: None,
+    },
+];
+/// Look up a built-in format by name, ignoring case.
+pub fn find_format(name: &str) -> Option<&'static Format> {
+    FORMATS
+        .iter()
+        .find(|format| format.name.eq_ignore_ascii_case(name))
+}
+
+/// One way a deck breaks a format's rules.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Violation {
+    TooFewCards {
+        min: usize,
+        got: usize,
+    },
+    TooManyCopies {
+        name: &'static str,
+        max: usize,
+        got: usize,
+    },
+    NotInPool {
+        name: &'static str,
+    },
+}
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Violation::TooFewCards { min, got } => {
+                write!(f, "has {} cards, fewer than {}", got, min)
+            }
+            Violation::TooManyCopies { name, max, got } => {
+                write!(f, "has {} copies of {}, more than {}", got, name, max)
+            }
+            Violation::NotInPool { name } => write!(f, "has {}, which is not allowed", name),
+        }
+    }
+}
+
+impl Format {
+    /// Every violation in `deck`, with each card reported once
+    /// in the order it first appears.
+    pub fn validate(&self, deck: &[Card]) -> Result<(), Vec<Violation>> {
+        let mut violations = vec![];
+        if deck.len() < self.min_deck_size {
+            violations.push(Violation::TooFewCards {
+                min: self.min_deck_size,
+                got: deck.len(),
+            });
+        }
+        let mut names: Vec<&'static str> = vec![];
+        for card in deck {
+            if let Card::Creature(creature_card) = card {
+                if !names.contains(&creature_card.name()) {
+                    names.push(creature_card.name());
+                }
+            }
+        }
+        for name in names {
+            if let Some(pool) = self.card_pool {
+                if !pool.contains(&name) {
+                    violations.push(Violation::NotInPool { name });
+                }
+            }
+            if let Some(max) = self.max_copies {
+                let got = deck
+                    .iter()
+                    .filter(|card| matches!(card, Card::Creature(cc) if cc.name() == name))
+                    .count();
+                if got > max {
+                    violations.push(Violation::TooManyCopies { name, max, got });
+                }
+            }
+        }
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_data::CreatureCard;
+
+    fn copies(count: usize, name: &str) -> Vec<Card> {
+        vec![Card::Creature(CreatureCard::expect_named(name)); count]
+    }
+    fn format(name: &str) -> &'static Format {
+        find_format(name).expect("Built in")
+    }
+
+    #[test]
+    fn basic_lands_are_exempt_from_max_copies() {
+        let mut deck = vec![Card::Land; 56];
+        deck.extend(copies(4, "Watchwolf"));
+        assert_eq!(format("Constructed").validate(&deck), Ok(()));
+        assert_eq!(format("Singleton").validate(&deck).unwrap_err().len(), 1);
+    }
+    #[test]
+    fn every_violation_is_reported() {
+        let mut deck = copies(2, "Watchwolf");
+        deck.extend(copies(1, "Memnite"));
+        deck.extend(copies(3, "Memnite"));
+        let pool_format = Format {
+            card_pool: Some(&["Watchwolf"]),
+            ..*format("Singleton")
+        };
+        assert_eq!(
+            pool_format.validate(&deck),
+            Err(vec![
+                Violation::TooFewCards { min: 60, got: 6 },
+                Violation::TooManyCopies {
+                    name: "Watchwolf",
+                    max: 1,
+                    got: 2,
+                },
+                Violation::NotInPool { name: "Memnite" },
+                Violation::TooManyCopies {
+                    name: "Memnite",
+                    max: 1,
+                    got: 4,
+                },
+            ])
+        );
+    }
+    #[test]
+    fn anything_goes_allows_any_deck() {
+        assert_eq!(format("AnythingGoes").validate(&[]), Ok(()));
+        assert_eq!(
+            format("anythinggoes").validate(&copies(60, "Memnite")),
+            Ok(())
+        );
+    }
+}
@@ -0,0 +1,1708 @@
+use crate::cards::Keyword;
+use crate::events::{DamageTarget, EventLog, GameEvent};
+use crate::game_data::{
+    Card, Creature, DamageAssignment, IllegalAction, MainPhaseAction, MuliganChoice, PlayerView,
+};
+use crate::player::Strategy;
+use crate::replay::{Decision, DecisionLog, Replay};
+use rand::prelude::*;
+use rand::rngs::StdRng;
+use std::collections::{HashMap, HashSet};
+
+/// One seat of the game: the strategy and everything it owns.
+#[derive(Debug)]
+pub struct PlayerState {
+    strategy: Box<dyn Strategy>,
+    deck: Vec<Card>,
+    hand: Vec<Card>,
+    num_lands: u64,
+    /// Lands tapped for mana this turn.
+    tapped_lands: u64,
+    /// Whether a land has been played this turn.
+    played_land: bool,
+    creatures: Vec<Creature>,
+    life: i64,
+}
+#[derive(Debug, Eq, PartialEq)]
+enum DrawResult {
+    Empty,
+    Nonempty,
+}
+impl PlayerState {
+    fn new(strategy: Box<dyn Strategy>, deck: Vec<Card>) -> Self {
+        PlayerState {
+            strategy,
+            deck,
+            hand: vec![],
+            num_lands: 0,
+            tapped_lands: 0,
+            played_land: false,
+            creatures: vec![],
+            life: 20,
+        }
+    }
+    pub fn strategy(&self) -> &dyn Strategy {
+        self.strategy.as_ref()
+    }
+    pub fn life(&self) -> i64 {
+        self.life
+    }
+    pub fn hand(&self) -> &[Card] {
+        &self.hand
+    }
+    pub fn num_lands(&self) -> u64 {
+        self.num_lands
+    }
+    pub fn tapped_lands(&self) -> u64 {
+        self.tapped_lands
+    }
+    pub fn creatures(&self) -> &[Creature] {
+        &self.creatures
+    }
+    pub fn deck_size(&self) -> usize {
+        self.deck.len()
+    }
+    fn do_muligans(
+        &mut self,
+        player: usize,
+        rng: &mut StdRng,
+        log: &mut EventLog,
+        decisions: &mut DecisionLog,
+    ) -> Result<(), IllegalAction> {
+        let is_first = player == 0;
+        let mut num_muls = 0;
+        while num_muls < 7 {
+            self.deck.shuffle(rng);
+            // Decks smaller than 7 cards draw what they have.
+            for _ in 0..7 {
+                self.draw();
+            }
+            let hand_size = self.hand.len();
+            let perform_muligan = self.strategy.muligan_choice(&self.hand, num_muls, is_first);
+            decisions.record(player, 0, 0, || Decision::Muligan(perform_muligan.clone()));
+            if let MuliganChoice::KeepExcept(remove) = perform_muligan {
+                check_muligan(&remove, num_muls, hand_size)?;
+                for i in (0..hand_size).rev() {
+                    if remove.contains(&i) {
+                        let card = self.hand.remove(i);
+                        self.deck.insert(0, card);
+                    }
+                }
+                assert_eq!(self.hand.len(), hand_size - num_muls);
+                let hand = &self.hand;
+                log.record(|| GameEvent::Keep {
+                    player,
+                    hand: hand.clone(),
+                });
+                return Ok(());
+            }
+            let hand = &self.hand;
+            log.record(|| GameEvent::Muligan {
+                player,
+                hand: hand.clone(),
+            });
+            self.deck.append(&mut self.hand);
+            num_muls += 1;
+        }
+        // If mul down to 0, exit here.
+        assert!(self.hand.is_empty());
+        Ok(())
+    }
+    fn declare_attackers(
+        &mut self,
+        attackers: &[usize],
+        num_turn: u64,
+    ) -> Result<(), IllegalAction> {
+        check_attackers(attackers, &self.creatures, num_turn)?;
+        for &attacker in attackers {
+            let creature = &mut self.creatures[attacker];
+            if !creature.has(Keyword::Vigilance) {
+                creature.tapped = true;
+            }
+        }
+        Ok(())
+    }
+    fn handle_main_phase_action(
+        &mut self,
+        action: MainPhaseAction,
+        player: usize,
+        num_turn: u64,
+        log: &mut EventLog,
+    ) -> Result<(), IllegalAction> {
+        check_main_phase_action(
+            action,
+            &self.hand,
+            self.num_lands - self.tapped_lands,
+            self.played_land,
+        )?;
+        match action {
+            MainPhaseAction::PlayLand(index) => {
+                self.hand.remove(index);
+                self.num_lands += 1;
+                self.played_land = true;
+                log.record(|| GameEvent::LandPlayed { player });
+            }
+            MainPhaseAction::Cast(index) => {
+                if let Card::Creature(creature_card) = self.hand.remove(index) {
+                    self.tapped_lands += creature_card.cmc();
+                    self.creatures.push(Creature::new(&creature_card, num_turn));
+                    log.record(|| GameEvent::SpellCast {
+                        player,
+                        card: creature_card,
+                    });
+                }
+            }
+            MainPhaseAction::Pass => {}
+        }
+        Ok(())
+    }
+    fn handle_discard(
+        &mut self,
+        discard_indices: Vec<usize>,
+        player: usize,
+        log: &mut EventLog,
+    ) -> Result<(), IllegalAction> {
+        check_discard(&discard_indices, self.hand.len())?;
+        for &index in &discard_indices {
+            let card = &self.hand[index];
+            log.record(|| GameEvent::Discard {
+                player,
+                card: card.clone(),
+            });
+        }
+        let mut index = 0;
+        self.hand.retain(|_| {
+            let keep = !discard_indices.contains(&index);
+            index += 1;
+            keep
+        });
+        assert_eq!(self.hand.len(), 7, "Discard correct number of cards");
+        Ok(())
+    }
+    fn log_deaths(&self, dead_creatures: &[usize], player: usize, log: &mut EventLog) {
+        for &index in dead_creatures {
+            let card = self.creatures[index].card();
+            log.record(|| GameEvent::CreatureDied {
+                player,
+                index,
+                card: card.clone(),
+            });
+        }
+    }
+    // Lifelink from creature `source` dealing `amount` damage.
+    fn gain_life(&mut self, source: usize, amount: u64, player: usize, log: &mut EventLog) {
+        if amount == 0 {
+            return;
+        }
+        self.life += amount as i64;
+        log.record(|| GameEvent::LifeGained {
+            player,
+            source,
+            amount,
+        });
+    }
+    fn remove_dead(&mut self, dead_creatures: &[usize]) {
+        let prior_number_creatures = self.creatures.len();
+        let mut index = 0;
+        self.creatures.retain(|_| {
+            let keep = !dead_creatures.contains(&index);
+            index += 1;
+            keep
+        });
+        assert_eq!(
+            prior_number_creatures,
+            self.creatures.len() + dead_creatures.len(),
+            "Correct number of creatures die"
+        );
+    }
+    fn remove_damage(&mut self) {
+        for creature in &mut self.creatures {
+            creature.remove_damage();
+        }
+    }
+    fn draw(&mut self) -> DrawResult {
+        if self.deck.is_empty() {
+            DrawResult::Empty
+        } else {
+            let card = self.deck.pop().expect("Nonempty");
+            self.hand.push(card);
+            DrawResult::Nonempty
+        }
+    }
+    fn untap(&mut self) {
+        for creature in &mut self.creatures {
+            creature.tapped = false
+        }
+        self.tapped_lands = 0;
+        self.played_land = false;
+    }
+    fn view_and_mut<'a>(
+        &'a mut self,
+        other_state: &'a Self,
+        num_turn: u64,
+    ) -> (PlayerView<'a>, &'a mut dyn Strategy) {
+        let view = PlayerView {
+            num_turn,
+            life: self.life,
+            hand: &self.hand,
+            num_lands: self.num_lands,
+            tapped_lands: self.tapped_lands,
+            played_land: self.played_land,
+            creatures: &self.creatures,
+            deck_size: self.deck.len(),
+            oth_life: other_state.life,
+            oth_hand_size: other_state.hand.len(),
+            oth_lands: other_state.num_lands,
+            oth_tapped_lands: other_state.tapped_lands,
+            oth_creatures: &other_state.creatures,
+            oth_deck_size: other_state.deck.len(),
+        };
+        (view, self.strategy.as_mut())
+    }
+    fn print_player(&self, is_current_player: bool) {
+        print!(
+            "L: {}, C: {}, P: {:?}",
+            self.life,
+            self.deck.len(),
+            self.strategy
+        );
+        if is_current_player {
+            print!("   <<<");
+        }
+        println!();
+    }
+    fn print_hand(&self) {
+        let cards: Vec<String> = self
+            .hand
+            .iter()
+            .map(|card| match card {
+                Card::Creature(cc) => cc.to_string(),
+                Card::Land => "Land".to_string(),
+            })
+            .collect();
+        println!("H: {}", cards.join(", "));
+    }
+    fn print_battlefield(&self) {
+        let creatures: Vec<String> = self
+            .creatures
+            .iter()
+            .map(|creature| {
+                format!(
+                    "{} ({})",
+                    creature.name(),
+                    if creature.tapped { "t" } else { "u" }
+                )
+            })
+            .collect();
+        println!("B: {} lands    {}", self.num_lands, creatures.join(", "));
+    }
+}
+
+// The checks below are shared by the engine and `Human`,
+// which re-prompts rather than forfeiting.
+pub(crate) fn check_muligan(
+    remove: &[usize],
+    num_muls: usize,
+    hand_size: usize,
+) -> Result<(), IllegalAction> {
+    if remove.len() != num_muls {
+        return Err(IllegalAction::MuliganWrongCount {
+            expected: num_muls,
+            got: remove.len(),
+        });
+    }
+    check_indices(remove, hand_size).map_err(|index| IllegalAction::MuliganIndexInvalid { index })
+}
+pub(crate) fn check_attackers(
+    attackers: &[usize],
+    creatures: &[Creature],
+    num_turn: u64,
+) -> Result<(), IllegalAction> {
+    let mut seen = HashSet::new();
+    for &attacker in attackers {
+        let creature = creatures
+            .get(attacker)
+            .ok_or(IllegalAction::AttackerInvalid { index: attacker })?;
+        // Attacking twice with a creature counts as attacking tapped,
+        // even with vigilance.
+        if creature.tapped || !seen.insert(attacker) {
+            return Err(IllegalAction::AttackerTapped { index: attacker });
+        }
+        if creature.has(Keyword::Defender) {
+            return Err(IllegalAction::AttackerDefender { index: attacker });
+        }
+        if creature.summoning_sick(num_turn) {
+            return Err(IllegalAction::AttackerSummoningSick { index: attacker });
+        }
+    }
+    Ok(())
+}
+pub(crate) fn check_main_phase_action(
+    action: MainPhaseAction,
+    hand: &[Card],
+    untapped_lands: u64,
+    played_land: bool,
+) -> Result<(), IllegalAction> {
+    match action {
+        MainPhaseAction::PlayLand(index) => match hand.get(index) {
+            None => Err(IllegalAction::CardIndexInvalid { index }),
+            Some(Card::Creature(_)) => Err(IllegalAction::NotALand { index }),
+            Some(Card::Land) if played_land => Err(IllegalAction::SecondLand),
+            Some(Card::Land) => Ok(()),
+        },
+        MainPhaseAction::Cast(index) => match hand.get(index) {
+            None => Err(IllegalAction::CardIndexInvalid { index }),
+            Some(Card::Land) => Err(IllegalAction::CastLand { index }),
+            Some(Card::Creature(creature_card)) if creature_card.cmc() > untapped_lands => {
+                Err(IllegalAction::InsufficientMana {
+                    cost: creature_card.cmc(),
+                    available: untapped_lands,
+                })
+            }
+            Some(Card::Creature(_)) => Ok(()),
+        },
+        MainPhaseAction::Pass => Ok(()),
+    }
+}
+pub(crate) fn check_discard(indices: &[usize], hand_size: usize) -> Result<(), IllegalAction> {
+    let expected = hand_size - 7;
+    if indices.len() != expected {
+        return Err(IllegalAction::DiscardWrongCount {
+            expected,
+            got: indices.len(),
+        });
+    }
+    check_indices(indices, hand_size).map_err(|index| IllegalAction::DiscardIndexInvalid { index })
+}
+// Err with the first index that is out of range or repeated.
+fn check_indices(indices: &[usize], len: usize) -> Result<(), usize> {
+    let mut seen = HashSet::new();
+    for &index in indices {
+        if index >= len || !seen.insert(index) {
+            return Err(index);
+        }
+    }
+    Ok(())
+}
+// Group (blocker, attacker) pairs by attacker.
+pub(crate) fn arrange_blockers(
+    blocking_pairs: &[(usize, usize)],
+    attackers: &[usize],
+    attacking_creatures: &[Creature],
+    blocking_creatures: &[Creature],
+) -> Result<HashMap<usize, Vec<usize>>, IllegalAction> {
+    let mut blockers = HashSet::new();
+    let mut blocking_arrangement = HashMap::new();
+    for &(blocker, attacker) in blocking_pairs {
+        if !attackers.contains(&attacker) {
+            return Err(IllegalAction::NotAttacking { index: attacker });
+        }
+        let blocking_creature = blocking_creatures
+            .get(blocker)
+            .ok_or(IllegalAction::BlockerInvalid { index: blocker })?;
+        if blocking_creature.tapped {
+            return Err(IllegalAction::BlockerTapped { index: blocker });
+        }
+        if !blockers.insert(blocker) {
+            return Err(IllegalAction::BlockerBlocksTwice { index: blocker });
+        }
+        if !blocking_creature.can_block(&attacking_creatures[attacker]) {
+            return Err(IllegalAction::FlyerBlocked { blocker, attacker });
+        }
+        blocking_arrangement
+            .entry(attacker)
+            .or_insert(vec![])
+            .push(blocker);
+    }
+    for &attacker in attackers {
+        let num_blockers = blocking_arrangement.get(&attacker).map_or(0, Vec::len);
+        if num_blockers == 1 && attacking_creatures[attacker].has(Keyword::Menace) {
+            return Err(IllegalAction::MenaceBlockedByOne { attacker });
+        }
+    }
+    Ok(blocking_arrangement)
+}
+// The ordering must contain exactly the blocked attackers,
+// each with a permutation of its blockers.
+pub(crate) fn check_blocker_order(
+    blocking_arrangement: &HashMap<usize, Vec<usize>>,
+    ordered_blockers: &HashMap<usize, Vec<usize>>,
+) -> Result<(), IllegalAction> {
+    for attacker in blocking_arrangement.keys().chain(ordered_blockers.keys()) {
+        let mut default_blockers = blocking_arrangement.get(attacker).cloned();
+        let mut blockers = ordered_blockers.get(attacker).cloned();
+        default_blockers.iter_mut().for_each(|b| b.sort_unstable());
+        blockers.iter_mut().for_each(|b| b.sort_unstable());
+        if default_blockers != blockers {
+            return Err(IllegalAction::BlockerOrderMismatch {
+                attacker: *attacker,
+            });
+        }
+    }
+    Ok(())
+}
+// The damage from `attacker` that counts as lethal to `blocker`,
+// which with deathtouch is any damage at all.
+pub(crate) fn lethal_damage(attacker: &Creature, blocker: &Creature) -> u64 {
+    if attacker.has(Keyword::Deathtouch) {
+        blocker.lethal_damage().min(1)
+    } else {
+        blocker.lethal_damage()
+    }
+}
+// `lethal_damage` is what each blocker still needs, in damage order.
+pub(crate) fn check_damage_assignment(
+    assignment: &DamageAssignment,
+    attacker: usize,
+    power: u64,
+    trample: bool,
+    lethal_damage: &[u64],
+) -> Result<(), IllegalAction> {
+    if assignment.blockers.len() != lethal_damage.len() {
+        return Err(IllegalAction::DamageWrongBlockers { attacker });
+    }
+    let total = assignment
+        .blockers
+        .iter()
+        .fold(assignment.player, |total, &amount| {
+            total.saturating_add(amount)
+        });
+    if total != power {
+        return Err(IllegalAction::DamageWrongTotal {
+            attacker,
+            expected: power,
+            got: total,
+        });
+    }
+    if assignment.player > 0 && !trample {
+        return Err(IllegalAction::DamageToPlayerWithoutTrample { attacker });
+    }
+    // No damage past a blocker until it has lethal damage
+    let mut all_lethal = true;
+    for (&amount, &lethal) in assignment.blockers.iter().zip(lethal_damage) {
+        if amount > 0 && !all_lethal {
+            return Err(IllegalAction::DamagePastNonLethal { attacker });
+        }
+        all_lethal = all_lethal && amount >= lethal;
+    }
+    if assignment.player > 0 && !all_lethal {
+        return Err(IllegalAction::DamagePastNonLethal { attacker });
+    }
+    Ok(())
+}
+// Lethal damage to each blocker in order, then the rest
+// to the defending player with trample, or else to the last blocker.
+pub(crate) fn default_damage_assignment(
+    power: u64,
+    trample: bool,
+    lethal_damage: &[u64],
+) -> DamageAssignment {
+    let mut remaining = power;
+    let mut blockers = vec![];
+    for &lethal in lethal_damage {
+        let amount = lethal.min(remaining);
+        blockers.push(amount);
+        remaining -= amount;
+    }
+    let player = if trample {
+        remaining
+    } else {
+        if let Some(last) = blockers.last_mut() {
+            *last += remaining;
+        }
+        0
+    };
+    DamageAssignment { blockers, player }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Winner {
+    Player1,
+    Player2,
+    Draw,
+}
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EndReason {
+    Life,
+    Decking,
+    /// The game reached its turn limit; always a draw.
+    TurnLimit,
+    /// No life was lost and no creature died for the stalemate window;
+    /// always a draw.
+    Stalemate,
+    /// The loser's strategy made an illegal decision.
+    Forfeit(IllegalAction),
+}
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct GameOutcome {
+    pub winner: Winner,
+    pub reason: EndReason,
+}
+// The creatures in one combat by index, and those that have died in it.
+struct Combat {
+    attackers: Vec<usize>,
+    // Every attacker's blockers in damage order, empty if unblocked
+    blockers: HashMap<usize, Vec<usize>>,
+    dead_attackers: Vec<usize>,
+    dead_blockers: Vec<usize>,
+}
+/// How much of the game to show on stdout.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Printout {
+    PrintAndPause,
+    Print,
+    Nothing,
+    /// Full-screen board, pausing after each phase.
+    /// Needs an active `tui::Session`.
+    #[cfg(feature = "tui")]
+    Tui,
+}
+impl Printout {
+    // The full-screen board shows the event log.
+    fn shows_events(self) -> bool {
+        #[cfg(feature = "tui")]
+        if self == Printout::Tui {
+            return true;
+        }
+        false
+    }
+}
+#[derive(Debug)]
+pub struct GameState {
+    player_states: [PlayerState; 2],
+    num_turn: u64,
+    current_player_index: usize,
+    printout: Printout,
+    seed: u64,
+    rng: StdRng,
+    flipped: bool,
+    turn_limit: Option<u64>,
+    stalemate_turns: Option<u64>,
+    last_progress_turn: u64,
+    log: EventLog,
+    coin_flipped: bool,
+    initial_decks: Option<[Vec<Card>; 2]>,
+    decisions: DecisionLog,
+    started: bool,
+    outcome: Option<GameOutcome>,
+}
+impl GameState {
+    /// Like `new`, but a coin flip drawn from the seeded RNG decides
+    /// which player goes first.
+    pub fn new_with_flip(
+        player1: Box<dyn Strategy>,
+        deck1: Vec<Card>,
+        player2: Box<dyn Strategy>,
+        deck2: Vec<Card>,
+        printout: Printout,
+        seed: u64,
+    ) -> Self {
+        let mut game = GameState::new(player1, deck1, player2, deck2, printout, seed);
+        game.coin_flipped = true;
+        let player1_first = game.rng.gen::<f64>() < 0.5;
+        if !player1_first {
+            game.player_states.swap(0, 1);
+            game.flipped = true;
+        }
+        game
+    }
+    /// All randomness in the game is drawn from an RNG seeded with `seed`,
+    /// so the same seed, decks and strategies always replay the same game.
+    /// `player1` goes first, piloting `deck1`.
+    /// Any deck can be given to any strategy;
+    /// `Strategy::make_deck` is each strategy's own choice.
+    pub fn new(
+        player1: Box<dyn Strategy>,
+        deck1: Vec<Card>,
+        player2: Box<dyn Strategy>,
+        deck2: Vec<Card>,
+        printout: Printout,
+        seed: u64,
+    ) -> Self {
+        let mut log = EventLog::default();
+        if printout.shows_events() {
+            log.enable();
+        }
+        GameState {
+            player_states: [
+                PlayerState::new(player1, deck1),
+                PlayerState::new(player2, deck2),
+            ],
+            num_turn: 1,
+            current_player_index: 0,
+            printout,
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            flipped: false,
+            turn_limit: None,
+            stalemate_turns: None,
+            last_progress_turn: 0,
+            log,
+            coin_flipped: false,
+            initial_decks: None,
+            decisions: DecisionLog::default(),
+            started: false,
+            outcome: None,
+        }
+    }
+    // The game a `Replay` recorded, fast-forwarded to the start of
+    // `player`'s turn `num_turn` without printing.
+    pub(crate) fn rebuild(
+        replay: &Replay,
+        player1: Box<dyn Strategy>,
+        player2: Box<dyn Strategy>,
+        printout: Printout,
+        num_turn: u64,
+        player: usize,
+    ) -> Self {
+        let [deck1, deck2] = replay.decks.clone();
+        let mut game = GameState::new(
+            player1,
+            deck1,
+            player2,
+            deck2,
+            Printout::Nothing,
+            replay.seed,
+        )
+        .with_turn_limit(replay.turn_limit)
+        .with_stalemate_turns(replay.stalemate_turns)
+        .with_decision_log();
+        if replay.coin_flipped {
+            game.coin_flipped = true;
+            game.rng.gen::<f64>();
+        }
+        game.flipped = replay.flipped;
+        if printout.shows_events() {
+            game.log.enable();
+        }
+        game.play_until(num_turn, player);
+        game.printout = printout;
+        game
+    }
+    /// Record a `GameEvent` for everything that happens during `play`.
+    pub fn with_event_log(mut self) -> Self {
+        self.log.enable();
+        self
+    }
+    /// Events so far, empty unless built `with_event_log`.
+    pub fn events(&self) -> &[GameEvent] {
+        self.log.events()
+    }
+    /// Record every decision the strategies make during `play`,
+    /// so the game can be rebuilt from its `replay`.
+    pub fn with_decision_log(mut self) -> Self {
+        self.initial_decks = Some([
+            self.player_states[0].deck.clone(),
+            self.player_states[1].deck.clone(),
+        ]);
+        self.decisions.enable();
+        self
+    }
+    /// Everything needed to rebuild the game so far,
+    /// `None` unless built `with_decision_log`.
+    pub fn replay(&self) -> Option<Replay> {
+        let decks = self.initial_decks.clone()?;
+        Some(Replay {
+            seed: self.seed,
+            coin_flipped: self.coin_flipped,
+            flipped: self.flipped,
+            turn_limit: self.turn_limit,
+            stalemate_turns: self.stalemate_turns,
+            decks,
+            decisions: self.decisions.decisions().unwrap_or(&[]).to_vec(),
+        })
+    }
+    /// End the game in a draw once `num_turn` would exceed `turn_limit`.
+    pub fn with_turn_limit(mut self, turn_limit: Option<u64>) -> Self {
+        self.turn_limit = turn_limit;
+        self
+    }
+    /// End the game in a draw after `stalemate_turns` full turns
+    /// with no life lost and no creature dying. `None`, the default, never does.
+    pub fn with_stalemate_turns(mut self, stalemate_turns: Option<u64>) -> Self {
+        self.stalemate_turns = stalemate_turns;
+        self
+    }
+    pub fn num_turn(&self) -> u64 {
+        self.num_turn
+    }
+    pub fn current_player_index(&self) -> usize {
+        self.current_player_index
+    }
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+    /// Whether `new_with_flip` put `player2` in the first seat.
+    pub fn flipped(&self) -> bool {
+        self.flipped
+    }
+    /// Player 0 goes first.
+    pub fn player_state(&self, index: usize) -> &PlayerState {
+        &self.player_states[index]
+    }
+    /// The result, once the game is over.
+    pub fn outcome(&self) -> Option<GameOutcome> {
+        self.outcome
+    }
+    /// Play the game to completion, or from wherever it was left off.
+    pub fn play(&mut self) -> GameOutcome {
+        loop {
+            if let Some(outcome) = self.outcome {
+                return outcome;
+            }
+            self.advance();
+        }
+    }
+    /// Play up to the start of `player`'s turn `num_turn`,
+    /// stopping early if the game ends first.
+    pub fn play_until(&mut self, num_turn: u64, player: usize) -> Option<GameOutcome> {
+        while self.outcome.is_none()
+            && (!self.started || (self.num_turn, self.current_player_index) < (num_turn, player))
+        {
+            self.advance();
+        }
+        self.outcome
+    }
+    // Muligans, or one turn.
+    fn advance(&mut self) {
+        let outcome = if self.started {
+            self.play_turn()
+        } else {
+            self.started = true;
+            self.muligans()
+        };
+        if let Some(outcome) = outcome {
+            let num_turn = self.num_turn;
+            self.log.record(|| GameEvent::GameEnd { num_turn, outcome });
+            self.outcome = Some(outcome);
+        }
+    }
+    fn muligans(&mut self) -> Option<GameOutcome> {
+        for index in 0..2 {
+            let player_state = &mut self.player_states[index];
+            if let Err(action) =
+                player_state.do_muligans(index, &mut self.rng, &mut self.log, &mut self.decisions)
+            {
+                return Some(self.forfeit(index, action));
+            }
+        }
+        None
+    }
+    fn play_turn(&mut self) -> Option<GameOutcome> {
+        let num_turn = self.num_turn;
+        let current_player_index = self.current_player_index;
+        if let Some(reason) = self.draw_reason() {
+            self.handle_printout(match reason {
+                EndReason::TurnLimit => "Game over due to turn limit",
+                _ => "Game over due to stalemate",
+            });
+            return Some(GameOutcome {
+                winner: Winner::Draw,
+                reason,
+            });
+        }
+        self.log.record(|| GameEvent::TurnStart {
+            player: current_player_index,
+            num_turn,
+        });
+        // Untap
+        let (current_state, _) = Self::states_mut(&mut self.player_states, current_player_index);
+        current_state.untap();
+        if !(num_turn == 1 && current_player_index == 0) {
+            self.handle_printout("Untap");
+        }
+        // Draw step
+        if !(num_turn == 1 && current_player_index == 0) {
+            let current_state = &mut self.player_states[current_player_index];
+            let draw_result = current_state.draw();
+            if let DrawResult::Empty = draw_result {
+                self.handle_printout("Game over due to decking");
+                // Game over due to decking
+                let winner = if current_player_index == 0 {
+                    Winner::Player2
+                } else {
+                    Winner::Player1
+                };
+                return Some(GameOutcome {
+                    winner,
+                    reason: EndReason::Decking,
+                });
+            }
+            self.log.record(|| GameEvent::Draw {
+                player: current_player_index,
+                card: current_state.hand.last().expect("Just drew").clone(),
+            });
+        }
+        self.handle_printout("Draw");
+        if let Some(outcome) = self.main_phase(true) {
+            return Some(outcome);
+        }
+        // Current player attacks
+        let (current_state, other_state) =
+            Self::states_mut(&mut self.player_states, current_player_index);
+        let (current_view, current_player) = current_state.view_and_mut(other_state, num_turn);
+        let attackers = current_player.attack(current_view);
+        self.decisions
+            .record(current_player_index, num_turn, current_player_index, || {
+                Decision::Attack(attackers.clone())
+            });
+        if let Err(action) = current_state.declare_attackers(&attackers, num_turn) {
+            return Some(self.forfeit(current_player_index, action));
+        }
+        if !attackers.is_empty() {
+            self.log.record(|| GameEvent::AttackDeclared {
+                player: current_player_index,
+                attackers: attackers.clone(),
+            });
+            self.handle_printout("Attack");
+        }
+        // Other player blocks
+        let (current_state, other_state) =
+            Self::states_mut(&mut self.player_states, current_player_index);
+        let (other_view, other_player) = other_state.view_and_mut(current_state, num_turn);
+        let blocking_pairs = other_player.block(other_view, &attackers);
+        self.decisions.record(
+            1 - current_player_index,
+            num_turn,
+            current_player_index,
+            || Decision::Block(blocking_pairs.clone()),
+        );
+        let blocking_arrangement = match arrange_blockers(
+            &blocking_pairs,
+            &attackers,
+            &current_state.creatures,
+            &other_state.creatures,
+        ) {
+            Ok(blocking_arrangement) => blocking_arrangement,
+            Err(action) => return Some(self.forfeit(1 - current_player_index, action)),
+        };
+        if !blocking_pairs.is_empty() {
+            self.log.record(|| GameEvent::BlocksDeclared {
+                player: 1 - current_player_index,
+                blocks: blocking_pairs,
+            });
+        }
+        // Current player orders blockers
+        let (current_view, current_player) = current_state.view_and_mut(other_state, num_turn);
+        let ordered_blockers = current_player.order_blockers(current_view, &blocking_arrangement);
+        self.decisions
+            .record(current_player_index, num_turn, current_player_index, || {
+                Decision::OrderBlockers(ordered_blockers.clone())
+            });
+        if let Err(action) = check_blocker_order(&blocking_arrangement, &ordered_blockers) {
+            return Some(self.forfeit(current_player_index, action));
+        }
+        let mut all_blockers = ordered_blockers;
+        // Add in unblocked attackers
+        for &attacker in &attackers {
+            all_blockers.entry(attacker).or_insert(vec![]);
+        }
+        for &attacker in &attackers {
+            let blockers = &all_blockers[&attacker];
+            if !blockers.is_empty() {
+                self.log.record(|| GameEvent::BlockersOrdered {
+                    player: current_player_index,
+                    attacker,
+                    blockers: blockers.clone(),
+                });
+            }
+        }
+        // Damage, check for dead creatures, lethal damage
+        let life_before_damage = other_state.life;
+        let any_first_strike = attackers
+            .iter()
+            .map(|&a| &current_state.creatures[a])
+            .chain(
+                all_blockers
+                    .values()
+                    .flatten()
+                    .map(|&b| &other_state.creatures[b]),
+            )
+            .any(|creature| creature.deals_damage(true));
+        let mut combat = Combat {
+            attackers,
+            blockers: all_blockers,
+            dead_attackers: vec![],
+            dead_blockers: vec![],
+        };
+        for first_strike in [true, false] {
+            if first_strike && !any_first_strike {
+                continue;
+            }
+            if let Err(action) = self.damage_step(&mut combat, first_strike) {
+                return Some(self.forfeit(current_player_index, action));
+            }
+        }
+        let (current_state, other_state) =
+            Self::states_mut(&mut self.player_states, current_player_index);
+        let made_progress = other_state.life != life_before_damage
+            || !combat.dead_attackers.is_empty()
+            || !combat.dead_blockers.is_empty();
+        current_state.remove_dead(&combat.dead_attackers);
+        other_state.remove_dead(&combat.dead_blockers);
+        let attackers = combat.attackers;
+
+        if other_state.life <= 0 {
+            self.handle_printout("Game over due to life");
+            // Game over due to life loss
+            let winner = if current_player_index == 0 {
+                Winner::Player1
+            } else {
+                Winner::Player2
+            };
+            return Some(GameOutcome {
+                winner,
+                reason: EndReason::Life,
+            });
+        }
+        if made_progress {
+            self.last_progress_turn = num_turn;
+        }
+        if !attackers.is_empty() {
+            self.handle_printout("Damage");
+        }
+        if let Some(outcome) = self.main_phase(false) {
+            return Some(outcome);
+        }
+        // Discard
+        let (current_state, other_state) =
+            Self::states_mut(&mut self.player_states, current_player_index);
+        if current_state.hand.len() > 7 {
+            let (view, player) = current_state.view_and_mut(other_state, num_turn);
+            let discard_indices = player.discard(view);
+            self.decisions
+                .record(current_player_index, num_turn, current_player_index, || {
+                    Decision::Discard(discard_indices.clone())
+                });
+            if let Err(action) =
+                current_state.handle_discard(discard_indices, current_player_index, &mut self.log)
+            {
+                return Some(self.forfeit(current_player_index, action));
+            }
+            self.handle_printout("Discard");
+        }
+        // Cleanup
+        for state in &mut self.player_states {
+            state.remove_damage();
+        }
+        // Switch current player, increment turn number as appropriate
+        self.current_player_index = 1 - self.current_player_index;
+        if self.current_player_index == 0 {
+            self.num_turn += 1;
+        }
+        None
+    }
+    // One combat damage step, either the first-strike step or the regular one.
+    // Creatures that died in an earlier step deal and take no damage.
+    // Those that die now are logged but stay on the battlefield
+    // until combat ends, so that indices keep their meaning.
+    fn damage_step(
+        &mut self,
+        combat: &mut Combat,
+        first_strike: bool,
+    ) -> Result<(), IllegalAction> {
+        let num_turn = self.num_turn;
+        let current_player_index = self.current_player_index;
+        let other_player_index = 1 - current_player_index;
+        let (current_state, other_state) =
+            Self::states_mut(&mut self.player_states, current_player_index);
+        for &attacker in &combat.attackers {
+            if combat.dead_attackers.contains(&attacker) {
+                continue;
+            }
+            let blockers: Vec<usize> = combat.blockers[&attacker]
+                .iter()
+                .copied()
+                .filter(|blocker| !combat.dead_blockers.contains(blocker))
+                .collect();
+            let attacking_creature = &current_state.creatures[attacker];
+            // Without the right strike, it only takes damage this step
+            let attacker_pow = if attacking_creature.deals_damage(first_strike) {
+                attacking_creature.pow()
+            } else {
+                0
+            };
+            let deathtouch = attacking_creature.has(Keyword::Deathtouch);
+            let lifelink = attacking_creature.has(Keyword::Lifelink);
+            let attacker_dealt = if combat.blockers[&attacker].is_empty() {
+                other_state.life -= attacker_pow as i64;
+                if attacker_pow > 0 {
+                    self.log.record(|| GameEvent::Damage {
+                        player: current_player_index,
+                        source: attacker,
+                        target: DamageTarget::Player(other_player_index),
+                        amount: attacker_pow,
+                    });
+                }
+                attacker_pow
+            } else {
+                let trample = attacking_creature.has(Keyword::Trample);
+                let lethal_damage: Vec<u64> = blockers
+                    .iter()
+                    .map(|&blocker| {
+                        lethal_damage(attacking_creature, &other_state.creatures[blocker])
+                    })
+                    .collect();
+                let default_assignment =
+                    default_damage_assignment(attacker_pow, trample, &lethal_damage);
+                let has_choice = blockers.len() > 1 || (trample && !blockers.is_empty());
+                let assignment = if attacker_pow > 0 && has_choice {
+                    let (view, player) = current_state.view_and_mut(other_state, num_turn);
+                    let assignment =
+                        player.assign_damage(view, attacker, &blockers, &default_assignment);
+                    self.decisions.record(
+                        current_player_index,
+                        num_turn,
+                        current_player_index,
+                        || Decision::AssignDamage(assignment.clone()),
+                    );
+                    check_damage_assignment(
+                        &assignment,
+                        attacker,
+                        attacker_pow,
+                        trample,
+                        &lethal_damage,
+                    )?;
+                    assignment
+                } else {
+                    default_assignment
+                };
+                for (&blocker, &amount) in blockers.iter().zip(&assignment.blockers) {
+                    if amount == 0 {
+                        continue;
+                    }
+                    other_state.creatures[blocker].take_damage(amount, deathtouch);
+                    self.log.record(|| GameEvent::Damage {
+                        player: current_player_index,
+                        source: attacker,
+                        target: DamageTarget::Creature {
+                            player: other_player_index,
+                            index: blocker,
+                        },
+                        amount,
+                    });
+                }
+                if assignment.player > 0 {
+                    other_state.life -= assignment.player as i64;
+                    self.log.record(|| GameEvent::Damage {
+                        player: current_player_index,
+                        source: attacker,
+                        target: DamageTarget::Player(other_player_index),
+                        amount: assignment.player,
+                    });
+                }
+                assignment.blockers.iter().sum::<u64>() + assignment.player
+            };
+            if lifelink {
+                current_state.gain_life(
+                    attacker,
+                    attacker_dealt,
+                    current_player_index,
+                    &mut self.log,
+                );
+            }
+            for &blocker in &blockers {
+                let blocking_creature = &other_state.creatures[blocker];
+                if !blocking_creature.deals_damage(first_strike) {
+                    continue;
+                }
+                let blocker_pow = blocking_creature.pow();
+                let deathtouch = blocking_creature.has(Keyword::Deathtouch);
+                let lifelink = blocking_creature.has(Keyword::Lifelink);
+                current_state.creatures[attacker].take_damage(blocker_pow, deathtouch);
+                if blocker_pow > 0 {
+                    self.log.record(|| GameEvent::Damage {
+                        player: other_player_index,
+                        source: blocker,
+                        target: DamageTarget::Creature {
+                            player: current_player_index,
+                            index: attacker,
+                        },
+                        amount: blocker_pow,
+                    });
+                }
+                if lifelink {
+                    other_state.gain_life(blocker, blocker_pow, other_player_index, &mut self.log);
+                }
+            }
+        }
+        let dead_attackers: Vec<usize> = combat
+            .attackers
+            .iter()
+            .copied()
+            .filter(|attacker| !combat.dead_attackers.contains(attacker))
+            .filter(|&attacker| current_state.creatures[attacker].destroyed())
+            .collect();
+        let dead_blockers: Vec<usize> = combat
+            .attackers
+            .iter()
+            .flat_map(|attacker| &combat.blockers[attacker])
+            .copied()
+            .filter(|blocker| !combat.dead_blockers.contains(blocker))
+            .filter(|&blocker| other_state.creatures[blocker].destroyed())
+            .collect();
+        current_state.log_deaths(&dead_attackers, current_player_index, &mut self.log);
+        other_state.log_deaths(&dead_blockers, other_player_index, &mut self.log);
+        combat.dead_attackers.extend(dead_attackers);
+        combat.dead_blockers.extend(dead_blockers);
+        Ok(())
+    }
+    fn forfeit(&self, loser: usize, action: IllegalAction) -> GameOutcome {
+        self.handle_printout(&format!("Game over due to illegal action: {}", action));
+        GameOutcome {
+            winner: if loser == 0 {
+                Winner::Player2
+            } else {
+                Winner::Player1
+            },
+            reason: EndReason::Forfeit(action),
+        }
+    }
+    fn draw_reason(&self) -> Option<EndReason> {
+        if let Some(turn_limit) = self.turn_limit {
+            if self.num_turn > turn_limit {
+                return Some(EndReason::TurnLimit);
+            }
+        }
+        if let Some(stalemate_turns) = self.stalemate_turns {
+            if self.num_turn - self.last_progress_turn > stalemate_turns {
+                return Some(EndReason::Stalemate);
+            }
+        }
+        None
+    }
+    fn states_mut(
+        player_states: &mut [PlayerState; 2],
+        current_player: usize,
+    ) -> (&mut PlayerState, &mut PlayerState) {
+        let (first_state, rest) = player_states.split_first_mut().expect("Multiple players");
+        let second_state = &mut rest[0];
+        if current_player == 0 {
+            (first_state, second_state)
+        } else {
+            (second_state, first_state)
+        }
+    }
+    // The current player's main phase before or after combat:
+    // one action at a time until they pass.
+    // The precombat one is only shown if something was played.
+    fn main_phase(&mut self, precombat: bool) -> Option<GameOutcome> {
+        let num_turn = self.num_turn;
+        let current_player_index = self.current_player_index;
+        let mut played_something = false;
+        loop {
+            let (current_state, other_state) =
+                Self::states_mut(&mut self.player_states, current_player_index);
+            let (view, player) = current_state.view_and_mut(other_state, num_turn);
+            let main_phase_action = if precombat {
+                player.precombat_main_phase(view)
+            } else {
+                player.main_phase(view)
+            };
+            self.decisions
+                .record(current_player_index, num_turn, current_player_index, || {
+                    if precombat {
+                        Decision::PrecombatMainPhase(main_phase_action)
+                    } else {
+                        Decision::MainPhase(main_phase_action)
+                    }
+                });
+            if main_phase_action == MainPhaseAction::Pass {
+                break;
+            }
+            if let Err(action) = current_state.handle_main_phase_action(
+                main_phase_action,
+                current_player_index,
+                num_turn,
+                &mut self.log,
+            ) {
+                return Some(self.forfeit(current_player_index, action));
+            }
+            played_something = true;
+        }
+        if !precombat {
+            self.handle_printout("Postcombat main phase");
+        } else if played_something {
+            self.handle_printout("Precombat main phase");
+        }
+        None
+    }
+    fn handle_printout(&self, phase: &str) {
+        if let Printout::Nothing = self.printout {
+            return;
+        }
+        #[cfg(feature = "tui")]
+        if let Printout::Tui = self.printout {
+            crate::tui::show_game(self, phase);
+            return;
+        }
+        println!("{} {}", phase, self.num_turn);
+        let state0 = &self.player_states[0];
+        let state1 = &self.player_states[1];
+        state0.print_player(self.current_player_index == 0);
+        state0.print_hand();
+        state0.print_battlefield();
+        state1.print_battlefield();
+        state1.print_hand();
+        state1.print_player(self.current_player_index == 1);
+
+        if let Printout::PrintAndPause = self.printout {
+            use std::io::{stdin, stdout, Write};
+            let mut s = String::new();
+            println!("Enter to continue");
+            stdout().flush().expect("Flushed");
+            stdin().read_line(&mut s).expect("Continued");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_data::CreatureCard;
+
+    // Passes every decision, and assigns damage as told
+    // or else takes the default.
+    #[derive(Debug)]
+    struct Assigns(Option<DamageAssignment>);
+    impl Strategy for Assigns {
+        fn attack(&mut self, _view: PlayerView) -> Vec<usize> {
+            vec![]
+        }
+        fn assign_damage(
+            &mut self,
+            _view: PlayerView,
+            _attacker: usize,
+            _blockers: &[usize],
+            default_assignment: &DamageAssignment,
+        ) -> DamageAssignment {
+            self.0.clone().unwrap_or_else(|| default_assignment.clone())
+        }
+        fn main_phase(&mut self, _view: PlayerView) -> MainPhaseAction {
+            MainPhaseAction::Pass
+        }
+    }
+
+    fn creature(name: &str) -> Creature {
+        Creature::new(&CreatureCard::expect_named(name), 0)
+    }
+    // Player 0 attacks with every creature in `attackers`
+    // into player 1's `blockers`.
+    fn new_game(
+        attackers: &[&str],
+        blockers: &[&str],
+        assignment: Option<DamageAssignment>,
+    ) -> GameState {
+        let mut game = GameState::new(
+            Box::new(Assigns(assignment)),
+            vec![],
+            Box::new(Assigns(None)),
+            vec![],
+            Printout::Nothing,
+            0,
+        );
+        game.player_states[0].creatures = attackers.iter().map(|name| creature(name)).collect();
+        game.player_states[1].creatures = blockers.iter().map(|name| creature(name)).collect();
+        game
+    }
+    // Both damage steps, with `blocks` as (blocker, attacker) pairs
+    // in damage order.
+    fn fight(game: &mut GameState, blocks: &[(usize, usize)]) -> Result<Combat, IllegalAction> {
+        let attackers: Vec<usize> = (0..game.player_states[0].creatures.len()).collect();
+        let mut blockers: HashMap<usize, Vec<usize>> = attackers
+            .iter()
+            .map(|&attacker| (attacker, vec![]))
+            .collect();
+        for &(blocker, attacker) in blocks {
+            blockers
+                .get_mut(&attacker)
+                .expect("Attacking")
+                .push(blocker);
+        }
+        let mut combat = Combat {
+            attackers,
+            blockers,
+            dead_attackers: vec![],
+            dead_blockers: vec![],
+        };
+        for first_strike in [true, false] {
+            game.damage_step(&mut combat, first_strike)?;
+        }
+        Ok(combat)
+    }
+
+    // The one illegal decision a `Cheater` makes.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Cheat {
+        Nothing,
+        MissingAttacker,
+        TappedBlocker,
+        NoDiscard,
+        OverCost,
+        BlockerOrder,
+    }
+    // Attacks with everything it can, blocks the first attacker with
+    // everything untapped and casts what fits, except for its `Cheat`.
+    #[derive(Debug)]
+    struct Cheater(Cheat);
+    impl Strategy for Cheater {
+        fn attack(&mut self, view: PlayerView) -> Vec<usize> {
+            if self.0 == Cheat::MissingAttacker {
+                return vec![view.creatures.len()];
+            }
+            (0..view.creatures.len())
+                .filter(|&index| view.can_attack(index))
+                .collect()
+        }
+        fn block(&mut self, view: PlayerView, attackers: &[usize]) -> Vec<(usize, usize)> {
+            let tapped = self.0 == Cheat::TappedBlocker;
+            match attackers.first() {
+                Some(&attacker) => (0..view.creatures.len())
+                    .filter(|&index| view.creatures[index].tapped == tapped)
+                    .map(|index| (index, attacker))
+                    .collect(),
+                None => vec![],
+            }
+        }
+        fn order_blockers(
+            &mut self,
+            _view: PlayerView,
+            default_ordering: &HashMap<usize, Vec<usize>>,
+        ) -> HashMap<usize, Vec<usize>> {
+            let mut ordering = default_ordering.clone();
+            if self.0 == Cheat::BlockerOrder {
+                ordering.values_mut().for_each(|blockers| {
+                    blockers.pop();
+                });
+            }
+            ordering
+        }
+        fn main_phase(&mut self, view: PlayerView) -> MainPhaseAction {
+            if self.0 == Cheat::OverCost && !view.hand.is_empty() {
+                return MainPhaseAction::Cast(0);
+            }
+            let untapped_lands = view.num_lands - view.tapped_lands;
+            view.hand
+                .iter()
+                .position(|card| matches!(card, Card::Creature(cc) if cc.cmc() <= untapped_lands))
+                .map_or(MainPhaseAction::Pass, MainPhaseAction::Cast)
+        }
+        fn discard(&mut self, view: PlayerView) -> Vec<usize> {
+            if self.0 == Cheat::NoDiscard {
+                return vec![];
+            }
+            (0..view.hand.len() - 7).collect()
+        }
+    }
+    // How a game ends when the player in seat `cheater` makes its `cheat`
+    // and both play 40 copies of `card`.
+    fn cheat(cheater: usize, cheat: Cheat, card: &str) -> GameOutcome {
+        let deck = || vec![Card::Creature(CreatureCard::expect_named(card)); 40];
+        let mut cheats = [Cheat::Nothing; 2];
+        cheats[cheater] = cheat;
+        GameState::new(
+            Box::new(Cheater(cheats[0])),
+            deck(),
+            Box::new(Cheater(cheats[1])),
+            deck(),
+            Printout::Nothing,
+            0,
+        )
+        .play()
+    }
+    // Plays a land and casts what fits before combat,
+    // then attacks with everything it can.
+    #[derive(Debug)]
+    struct Rushes;
+    impl Strategy for Rushes {
+        fn attack(&mut self, view: PlayerView) -> Vec<usize> {
+            (0..view.creatures.len())
+                .filter(|&index| view.can_attack(index))
+                .collect()
+        }
+        fn precombat_main_phase(&mut self, view: PlayerView) -> MainPhaseAction {
+            let land = view.hand.iter().position(|card| card == &Card::Land);
+            let untapped_lands = view.num_lands - view.tapped_lands;
+            match land {
+                Some(index) if !view.played_land => MainPhaseAction::PlayLand(index),
+                _ => view
+                    .hand
+                    .iter()
+                    .position(
+                        |card| matches!(card, Card::Creature(cc) if cc.cmc() <= untapped_lands),
+                    )
+                    .map_or(MainPhaseAction::Pass, MainPhaseAction::Cast),
+            }
+        }
+        fn main_phase(&mut self, _view: PlayerView) -> MainPhaseAction {
+            MainPhaseAction::Pass
+        }
+    }
+    fn forfeit(winner: Winner, action: IllegalAction) -> GameOutcome {
+        GameOutcome {
+            winner,
+            reason: EndReason::Forfeit(action),
+        }
+    }
+
+    #[test]
+    fn first_strike_kills_blocker_before_it_deals_damage() {
+        let mut game = new_game(&["Youthful Knight"], &["Icehide Golem"], None);
+        let combat = fight(&mut game, &[(0, 0)]).unwrap();
+        assert_eq!(combat.dead_blockers, vec![0]);
+        assert!(combat.dead_attackers.is_empty());
+        assert_eq!(game.player_states[0].creatures[0].damage(), 0);
+    }
+    #[test]
+    fn first_strike_attacker_still_takes_damage_from_survivors() {
+        let mut game = new_game(&["Youthful Knight"], &["Watchwolf"], None);
+        let combat = fight(&mut game, &[(0, 0)]).unwrap();
+        assert!(combat.dead_blockers.is_empty());
+        assert_eq!(combat.dead_attackers, vec![0]);
+        assert_eq!(game.player_states[1].creatures[0].damage(), 2);
+    }
+    #[test]
+    fn double_strike_deals_damage_in_both_steps() {
+        let mut game = new_game(&["Fencing Ace"], &[], None);
+        fight(&mut game, &[]).unwrap();
+        assert_eq!(game.player_states[1].life, 18);
+
+        let mut game = new_game(&["Boros Swiftblade"], &["Icehide Golem"], None);
+        let combat = fight(&mut game, &[(0, 0)]).unwrap();
+        assert_eq!(game.player_states[1].creatures[0].damage(), 2);
+        assert_eq!(combat.dead_blockers, vec![0]);
+        assert_eq!(combat.dead_attackers, vec![0]);
+    }
+    #[test]
+    fn trample_assigns_the_excess_to_the_player() {
+        let mut game = new_game(&["Colossal Dreadmaw"], &["Kraken Hatchling"], None);
+        let combat = fight(&mut game, &[(0, 0)]).unwrap();
+        assert_eq!(combat.dead_blockers, vec![0]);
+        assert_eq!(game.player_states[1].life, 18);
+    }
+    #[test]
+    fn trample_is_rejected_past_a_non_lethal_blocker() {
+        let assignment = DamageAssignment {
+            blockers: vec![3],
+            player: 3,
+        };
+        assert_eq!(
+            check_damage_assignment(&assignment, 0, 6, true, &[4]),
+            Err(IllegalAction::DamagePastNonLethal { attacker: 0 })
+        );
+        let mut game = new_game(
+            &["Colossal Dreadmaw"],
+            &["Kraken Hatchling"],
+            Some(assignment),
+        );
+        assert_eq!(
+            fight(&mut game, &[(0, 0)]).err(),
+            Some(IllegalAction::DamagePastNonLethal { attacker: 0 })
+        );
+    }
+    #[test]
+    fn damage_to_the_player_needs_trample() {
+        let assignment = DamageAssignment {
+            blockers: vec![4],
+            player: 2,
+        };
+        assert_eq!(
+            check_damage_assignment(&assignment, 0, 6, false, &[4]),
+            Err(IllegalAction::DamageToPlayerWithoutTrample { attacker: 0 })
+        );
+    }
+    #[test]
+    fn deathtouch_makes_one_damage_lethal_in_damage_order() {
+        let nighthawk = creature("Vampire Nighthawk");
+        let hatchling = creature("Kraken Hatchling");
+        assert_eq!(lethal_damage(&nighthawk, &hatchling), 1);
+        assert_eq!(
+            default_damage_assignment(2, false, &[1, 1]),
+            DamageAssignment {
+                blockers: vec![1, 1],
+                player: 0,
+            }
+        );
+        let mut game = new_game(
+            &["Vampire Nighthawk"],
+            &["Kraken Hatchling", "Kraken Hatchling"],
+            None,
+        );
+        let combat = fight(&mut game, &[(0, 0), (1, 0)]).unwrap();
+        assert_eq!(combat.dead_blockers, vec![0, 1]);
+    }
+    #[test]
+    fn lifelink_gains_the_damage_dealt() {
+        let mut game = new_game(&["Vault Skirge"], &[], None);
+        fight(&mut game, &[]).unwrap();
+        assert_eq!(game.player_states[0].life, 21);
+        assert_eq!(game.player_states[1].life, 19);
+
+        let mut game = new_game(&["Icehide Golem"], &["Knight of Meadowgrain"], None);
+        fight(&mut game, &[(0, 0)]).unwrap();
+        assert_eq!(game.player_states[1].life, 22);
+    }
+    #[test]
+    fn indestructible_survives_lethal_damage() {
+        let mut game = new_game(&["Icehide Golem"], &["Darksteel Myr"], None);
+        let combat = fight(&mut game, &[(0, 0)]).unwrap();
+        assert!(combat.dead_blockers.is_empty());
+        assert_eq!(game.player_states[1].creatures[0].damage(), 2);
+
+        let mut game = new_game(&["Typhoid Rats"], &["Darksteel Myr"], None);
+        let combat = fight(&mut game, &[(0, 0)]).unwrap();
+        assert!(combat.dead_blockers.is_empty());
+    }
+    #[test]
+    fn games_without_progress_are_drawn() {
+        let passive = || {
+            GameState::new(
+                Box::new(Assigns(None)),
+                vec![Card::Land; 40],
+                Box::new(Assigns(None)),
+                vec![Card::Land; 40],
+                Printout::Nothing,
+                0,
+            )
+        };
+        let mut game = passive().with_turn_limit(Some(5));
+        let draw = |reason| GameOutcome {
+            winner: Winner::Draw,
+            reason,
+        };
+        assert_eq!(game.play(), draw(EndReason::TurnLimit));
+        assert_eq!(game.num_turn(), 6);
+        let mut game = passive().with_stalemate_turns(Some(3));
+        assert_eq!(game.play(), draw(EndReason::Stalemate));
+        assert_eq!(game.num_turn(), 4);
+        // Neither by default, so the game goes on until a deck runs out
+        assert_eq!(passive().play().reason, EndReason::Decking);
+    }
+    #[test]
+    fn creatures_cannot_attack_the_turn_they_arrive() {
+        let arrived = [Creature::new(&CreatureCard::expect_named("Watchwolf"), 2)];
+        assert_eq!(
+            check_attackers(&[0], &arrived, 2),
+            Err(IllegalAction::AttackerSummoningSick { index: 0 })
+        );
+        assert_eq!(check_attackers(&[0], &arrived, 3), Ok(()));
+    }
+    #[test]
+    fn defenders_cannot_attack() {
+        assert_eq!(
+            check_attackers(&[0], &[creature("Wall of Stone")], 1),
+            Err(IllegalAction::AttackerDefender { index: 0 })
+        );
+    }
+    #[test]
+    fn haste_creatures_attack_the_turn_they_are_cast() {
+        let goblin = Card::Creature(CreatureCard::expect_named("Raging Goblin"));
+        let mut deck = vec![Card::Land; 20];
+        deck.extend(vec![goblin; 20]);
+        let mut game = GameState::new(
+            Box::new(Rushes),
+            deck,
+            Box::new(Assigns(None)),
+            vec![Card::Land; 40],
+            Printout::Nothing,
+            0,
+        );
+        game.play_until(1, 1);
+        let creatures = game.player_states[0].creatures();
+        assert_eq!(creatures.len(), 1);
+        assert!(creatures[0].tapped);
+        assert_eq!(game.player_states[1].life, 19);
+    }
+    #[test]
+    fn vigilance_attackers_stay_untapped() {
+        let mut game = new_game(&["Serra Angel", "Watchwolf"], &[], None);
+        game.player_states[0].declare_attackers(&[0, 1], 1).unwrap();
+        let creatures = game.player_states[0].creatures();
+        assert!(!creatures[0].tapped);
+        assert!(creatures[1].tapped);
+    }
+    #[test]
+    fn flyers_are_blocked_only_by_flying_or_reach() {
+        let flyer = [creature("Wind Drake")];
+        assert_eq!(
+            arrange_blockers(&[(0, 0)], &[0], &flyer, &[creature("Watchwolf")]),
+            Err(IllegalAction::FlyerBlocked {
+                blocker: 0,
+                attacker: 0,
+            })
+        );
+        for blocker in &["Canopy Spider", "Suntail Hawk"] {
+            let arrangement =
+                arrange_blockers(&[(0, 0)], &[0], &flyer, &[creature(blocker)]).unwrap();
+            assert_eq!(arrangement[&0], vec![0]);
+        }
+    }
+    #[test]
+    fn menace_needs_two_blockers() {
+        let menace = [creature("Boggart Brute")];
+        let blockers = [creature("Memnite"), creature("Memnite")];
+        assert_eq!(
+            arrange_blockers(&[(1, 0)], &[0], &menace, &blockers),
+            Err(IllegalAction::MenaceBlockedByOne { attacker: 0 })
+        );
+        let arrangement = arrange_blockers(&[(1, 0), (0, 0)], &[0], &menace, &blockers).unwrap();
+        assert_eq!(arrangement[&0], vec![1, 0]);
+        assert!(arrange_blockers(&[], &[0], &menace, &blockers)
+            .unwrap()
+            .is_empty());
+    }
+    #[test]
+    fn attacking_with_a_missing_creature_forfeits() {
+        assert_eq!(
+            cheat(0, Cheat::MissingAttacker, "Memnite"),
+            forfeit(Winner::Player2, IllegalAction::AttackerInvalid { index: 0 })
+        );
+    }
+    #[test]
+    fn blocking_with_a_tapped_creature_forfeits() {
+        assert_eq!(
+            cheat(1, Cheat::TappedBlocker, "Memnite"),
+            forfeit(Winner::Player1, IllegalAction::BlockerTapped { index: 0 })
+        );
+    }
+    #[test]
+    fn discarding_too_few_cards_forfeits() {
+        assert_eq!(
+            cheat(1, Cheat::NoDiscard, "Watchwolf"),
+            forfeit(
+                Winner::Player1,
+                IllegalAction::DiscardWrongCount {
+                    expected: 1,
+                    got: 0,
+                }
+            )
+        );
+    }
+    #[test]
+    fn casting_without_the_mana_forfeits() {
+        assert_eq!(
+            cheat(0, Cheat::OverCost, "Watchwolf"),
+            forfeit(
+                Winner::Player2,
+                IllegalAction::InsufficientMana {
+                    cost: 2,
+                    available: 0,
+                }
+            )
+        );
+    }
+    #[test]
+    fn ordering_other_blockers_forfeits() {
+        assert_eq!(
+            cheat(0, Cheat::BlockerOrder, "Memnite"),
+            forfeit(
+                Winner::Player2,
+                IllegalAction::BlockerOrderMismatch { attacker: 0 }
+            )
+        );
+    }
+    #[test]
+    fn games_without_cheats_end_normally() {
+        assert_eq!(cheat(0, Cheat::Nothing, "Memnite").reason, EndReason::Life);
+    }
+}
@@ -0,0 +1,437 @@
+use crate::cards::{cards_with_stats, find_card, CardInfo, Keyword};
+use std::error::Error;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Card {
+    Land,
+    Creature(CreatureCard),
+}
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Card::Land => write!(f, "Land"),
+            Card::Creature(creature_card) => write!(f, "{}", creature_card),
+        }
+    }
+}
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CreatureCard {
+    info: &'static CardInfo,
+}
+impl CreatureCard {
+    fn new(info: &'static CardInfo) -> Self {
+        CreatureCard { info }
+    }
+    /// The creature with the given name in `CARDS`, ignoring case.
+    pub fn named(name: &str) -> Option<Self> {
+        find_card(name).map(CreatureCard::new)
+    }
+    /// The registry card with this name, which tests know exists.
+    #[cfg(test)]
+    pub(crate) fn expect_named(name: &str) -> Self {
+        CreatureCard::named(name).expect("In the registry")
+    }
+    /// The first vanilla creature in `CARDS` with these stats,
+    /// or if none of them is vanilla, the first with keywords
+    /// as long as they all have the same keywords.
+    pub fn try_new(cmc: u64, pow: u64, tou: u64) -> Result<Self, IllegalCreature> {
+        if let Some(info) = cards_with_stats(cmc, pow, tou).find(|info| info.keywords.is_empty()) {
+            return Ok(CreatureCard::new(info));
+        }
+        let mut candidates = cards_with_stats(cmc, pow, tou);
+        let first = candidates
+            .next()
+            .ok_or(IllegalCreature::NoCreature { cmc, pow, tou })?;
+        if candidates.all(|info| info.keywords == first.keywords) {
+            Ok(CreatureCard::new(first))
+        } else {
+            Err(IllegalCreature::Ambiguous { cmc, pow, tou })
+        }
+    }
+    pub fn info(&self) -> &'static CardInfo {
+        self.info
+    }
+    pub fn name(&self) -> &'static str {
+        self.info.name
+    }
+    pub fn cmc(&self) -> u64 {
+        self.info.cmc
+    }
+    pub fn pow(&self) -> u64 {
+        self.info.pow
+    }
+    pub fn tou(&self) -> u64 {
+        self.info.tou
+    }
+    pub fn keywords(&self) -> &'static [Keyword] {
+        self.info.keywords
+    }
+    pub fn has(&self, keyword: Keyword) -> bool {
+        self.info.has(keyword)
+    }
+}
+impl fmt::Display for CreatureCard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+/// Why a cost, power and toughness combination picks no creature in `CARDS`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IllegalCreature {
+    /// No creature has these stats.
+    NoCreature { cmc: u64, pow: u64, tou: u64 },
+    /// None of the creatures with these stats is vanilla, and their
+    /// keywords differ, so the card has to be named.
+    Ambiguous { cmc: u64, pow: u64, tou: u64 },
+}
+impl fmt::Display for IllegalCreature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            IllegalCreature::NoCreature { cmc, pow, tou } => {
+                write!(f, "no allowed creature is {}/{}/{}", cmc, pow, tou)
+            }
+            IllegalCreature::Ambiguous { cmc, pow, tou } => {
+                let names: Vec<&str> = cards_with_stats(cmc, pow, tou)
+                    .map(|info| info.name)
+                    .collect();
+                write!(
+                    f,
+                    "{}/{}/{} could be {}; name the card",
+                    cmc,
+                    pow,
+                    tou,
+                    names.join(" or ")
+                )
+            }
+        }
+    }
+}
+impl Error for IllegalCreature {}
+#[derive(Clone, Debug)]
+pub struct Creature {
+    card: CreatureCard,
+    pub tapped: bool,
+    arrived: u64,
+    damage: u64,
+    deathtouched: bool,
+}
+impl Creature {
+    /// A creature that came under its controller's control on turn `num_turn`.
+    pub fn new(creature_card: &CreatureCard, num_turn: u64) -> Self {
+        Creature {
+            card: creature_card.clone(),
+            tapped: false,
+            arrived: num_turn,
+            damage: 0,
+            deathtouched: false,
+        }
+    }
+    /// The turn it came under its controller's control.
+    pub fn arrived(&self) -> u64 {
+        self.arrived
+    }
+    /// Whether it arrived this turn without haste, so cannot attack yet.
+    pub fn summoning_sick(&self, num_turn: u64) -> bool {
+        self.arrived >= num_turn && !self.has(Keyword::Haste)
+    }
+    /// Whether it can be declared as an attacker on turn `num_turn`.
+    pub fn can_attack(&self, num_turn: u64) -> bool {
+        !self.tapped && !self.has(Keyword::Defender) && !self.summoning_sick(num_turn)
+    }
+    /// The card this creature was cast from.
+    pub fn card(&self) -> &CreatureCard {
+        &self.card
+    }
+    pub fn name(&self) -> &'static str {
+        self.card.name()
+    }
+    pub fn cmc(&self) -> u64 {
+        self.card.cmc()
+    }
+    pub fn pow(&self) -> u64 {
+        self.card.pow()
+    }
+    pub fn tou(&self) -> u64 {
+        self.card.tou()
+    }
+    pub fn has(&self, keyword: Keyword) -> bool {
+        self.card.has(keyword)
+    }
+    /// Damage marked on it this turn.
+    pub fn damage(&self) -> u64 {
+        self.damage
+    }
+    /// The damage still needed to destroy it,
+    /// leaving aside deathtouch and indestructible.
+    pub fn lethal_damage(&self) -> u64 {
+        self.tou().saturating_sub(self.damage)
+    }
+    /// Whether a creature with deathtouch damaged it this turn.
+    pub fn deathtouched(&self) -> bool {
+        self.deathtouched
+    }
+    /// Whether the damage marked on it destroys it.
+    pub fn destroyed(&self) -> bool {
+        !self.has(Keyword::Indestructible) && (self.lethal_damage() == 0 || self.deathtouched)
+    }
+    pub(crate) fn take_damage(&mut self, amount: u64, deathtouch: bool) {
+        self.damage += amount;
+        self.deathtouched |= deathtouch && amount > 0;
+    }
+    pub(crate) fn remove_damage(&mut self) {
+        self.damage = 0;
+        self.deathtouched = false;
+    }
+    /// Whether it deals combat damage in the first-strike step,
+    /// or in the regular step if `first_strike` is false.
+    pub fn deals_damage(&self, first_strike: bool) -> bool {
+        if first_strike {
+            self.has(Keyword::FirstStrike) || self.has(Keyword::DoubleStrike)
+        } else {
+            !self.has(Keyword::FirstStrike) || self.has(Keyword::DoubleStrike)
+        }
+    }
+    /// Whether it can block `attacker`, leaving aside menace,
+    /// which depends on the other blockers.
+    pub fn can_block(&self, attacker: &Creature) -> bool {
+        !self.tapped
+            && (!attacker.has(Keyword::Flying)
+                || self.has(Keyword::Flying)
+                || self.has(Keyword::Reach))
+    }
+}
+/// Either muligan or keep and return cards.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MuliganChoice {
+    Muligan,
+    KeepExcept(Vec<usize>),
+}
+/// The information a player has available
+#[derive(Clone, Copy, Debug)]
+pub struct PlayerView<'a> {
+    pub num_turn: u64,
+    pub life: i64,
+    pub hand: &'a [Card],
+    pub num_lands: u64,
+    /// Lands already tapped for mana this turn.
+    pub tapped_lands: u64,
+    /// Whether this turn's land has been played.
+    pub played_land: bool,
+    pub creatures: &'a [Creature],
+    pub deck_size: usize,
+    pub oth_life: i64,
+    pub oth_hand_size: usize,
+    pub oth_lands: u64,
+    pub oth_tapped_lands: u64,
+    pub oth_creatures: &'a [Creature],
+    pub oth_deck_size: usize,
+}
+impl<'a> PlayerView<'a> {
+    /// Whether your creature at `index` can attack this turn.
+    pub fn can_attack(&self, index: usize) -> bool {
+        self.creatures[index].can_attack(self.num_turn)
+    }
+    /// Whether your creature `blocker` can block the opponent's `attacker`,
+    /// leaving aside menace, which needs two or more blockers.
+    pub fn can_block(&self, blocker: usize, attacker: usize) -> bool {
+        self.creatures[blocker].can_block(&self.oth_creatures[attacker])
+    }
+}
+/// One step of a main phase. Indices are into the hand
+/// as the `PlayerView` given with the decision shows it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MainPhaseAction {
+    PlayLand(usize),
+    Cast(usize),
+    /// End the main phase.
+    Pass,
+}
+/// How a blocked attacker splits its combat damage.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DamageAssignment {
+    /// Damage to each blocker, in damage order.
+    pub blockers: Vec<u64>,
+    /// Damage to the defending player, only allowed with trample.
+    pub player: u64,
+}
+/// A decision a strategy returned that breaks the rules.
+/// Indices are as the strategy gave them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IllegalAction {
+    MuliganWrongCount {
+        expected: usize,
+        got: usize,
+    },
+    MuliganIndexInvalid {
+        index: usize,
+    },
+    AttackerInvalid {
+        index: usize,
+    },
+    AttackerTapped {
+        index: usize,
+    },
+    AttackerSummoningSick {
+        index: usize,
+    },
+    AttackerDefender {
+        index: usize,
+    },
+    BlockerInvalid {
+        index: usize,
+    },
+    BlockerTapped {
+        index: usize,
+    },
+    BlockerBlocksTwice {
+        index: usize,
+    },
+    FlyerBlocked {
+        blocker: usize,
+        attacker: usize,
+    },
+    MenaceBlockedByOne {
+        attacker: usize,
+    },
+    NotAttacking {
+        index: usize,
+    },
+    BlockerOrderMismatch {
+        attacker: usize,
+    },
+    DamageWrongBlockers {
+        attacker: usize,
+    },
+    DamageWrongTotal {
+        attacker: usize,
+        expected: u64,
+        got: u64,
+    },
+    DamagePastNonLethal {
+        attacker: usize,
+    },
+    DamageToPlayerWithoutTrample {
+        attacker: usize,
+    },
+    NotALand {
+        index: usize,
+    },
+    SecondLand,
+    CardIndexInvalid {
+        index: usize,
+    },
+    CastLand {
+        index: usize,
+    },
+    InsufficientMana {
+        cost: u64,
+        available: u64,
+    },
+    DiscardWrongCount {
+        expected: usize,
+        got: usize,
+    },
+    DiscardIndexInvalid {
+        index: usize,
+    },
+}
+impl fmt::Display for IllegalAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IllegalAction::MuliganWrongCount { expected, got } => write!(
+                f,
+                "kept a muligan returning {} cards instead of {}",
+                got, expected
+            ),
+            IllegalAction::MuliganIndexInvalid { index } => {
+                write!(f, "returned invalid or repeated hand index {}", index)
+            }
+            IllegalAction::AttackerInvalid { index } => {
+                write!(f, "attacked with nonexistent creature {}", index)
+            }
+            IllegalAction::AttackerTapped { index } => {
+                write!(f, "attacked with tapped creature {}", index)
+            }
+            IllegalAction::AttackerSummoningSick { index } => {
+                write!(f, "attacked with summoning-sick creature {}", index)
+            }
+            IllegalAction::AttackerDefender { index } => {
+                write!(f, "attacked with creature {}, which has defender", index)
+            }
+            IllegalAction::BlockerInvalid { index } => {
+                write!(f, "blocked with nonexistent creature {}", index)
+            }
+            IllegalAction::BlockerTapped { index } => {
+                write!(f, "blocked with tapped creature {}", index)
+            }
+            IllegalAction::BlockerBlocksTwice { index } => {
+                write!(f, "blocked twice with creature {}", index)
+            }
+            IllegalAction::FlyerBlocked { blocker, attacker } => write!(
+                f,
+                "blocked flying creature {} with creature {}, which has neither flying nor reach",
+                attacker, blocker
+            ),
+            IllegalAction::MenaceBlockedByOne { attacker } => {
+                write!(
+                    f,
+                    "blocked menacing creature {} with only one creature",
+                    attacker
+                )
+            }
+            IllegalAction::NotAttacking { index } => {
+                write!(f, "blocked creature {}, which is not attacking", index)
+            }
+            IllegalAction::BlockerOrderMismatch { attacker } => write!(
+                f,
+                "ordered blockers of attacker {} differently from how it was blocked",
+                attacker
+            ),
+            IllegalAction::DamageWrongBlockers { attacker } => write!(
+                f,
+                "assigned damage of attacker {} to a different number of blockers than it has",
+                attacker
+            ),
+            IllegalAction::DamageWrongTotal {
+                attacker,
+                expected,
+                got,
+            } => write!(
+                f,
+                "assigned {} damage for attacker {} with power {}",
+                got, attacker, expected
+            ),
+            IllegalAction::DamagePastNonLethal { attacker } => write!(
+                f,
+                "assigned damage of attacker {} past a blocker without lethal damage",
+                attacker
+            ),
+            IllegalAction::DamageToPlayerWithoutTrample { attacker } => write!(
+                f,
+                "assigned damage of attacker {} to the player without trample",
+                attacker
+            ),
+            IllegalAction::NotALand { index } => {
+                write!(f, "played hand index {} as a land", index)
+            }
+            IllegalAction::SecondLand => write!(f, "played a second land this turn"),
+            IllegalAction::CardIndexInvalid { index } => {
+                write!(f, "played invalid hand index {}", index)
+            }
+            IllegalAction::CastLand { index } => write!(f, "cast land at hand index {}", index),
+            IllegalAction::InsufficientMana { cost, available } => write!(
+                f,
+                "cast a creature costing {} with {} untapped lands",
+                cost, available
+            ),
+            IllegalAction::DiscardWrongCount { expected, got } => {
+                write!(f, "discarded {} cards instead of {}", got, expected)
+            }
+            IllegalAction::DiscardIndexInvalid { index } => {
+                write!(f, "discarded invalid or repeated hand index {}", index)
+            }
+        }
+    }
+}
+impl Error for IllegalAction {}
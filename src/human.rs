@@ -0,0 +1,364 @@
+use crate::cards::Keyword;
+use crate::game::{
+    arrange_blockers, check_attackers, check_blocker_order, check_damage_assignment, check_discard,
+    check_main_phase_action, check_muligan, lethal_damage,
+};
+use crate::game_data::{
+    Card, Creature, DamageAssignment, IllegalAction, MainPhaseAction, MuliganChoice, PlayerView,
+};
+use crate::player::Strategy;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+/// A person making every decision at a terminal.
+/// Shows the `PlayerView` before each decision and asks again
+/// until the answer is legal. Once the input is closed it makes
+/// the passing choice: keep, no attacks or blocks, and the defaults.
+pub struct Human {
+    input: Box<dyn BufRead>,
+    output: Box<dyn Write>,
+    input_closed: bool,
+}
+impl fmt::Debug for Human {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Human")
+    }
+}
+impl Human {
+    pub fn new(input: Box<dyn BufRead>, output: Box<dyn Write>) -> Self {
+        Human {
+            input,
+            output,
+            input_closed: false,
+        }
+    }
+    /// Play over stdin and stdout.
+    pub fn stdio() -> Self {
+        Human::new(
+            Box::new(io::BufReader::new(io::stdin())),
+            Box::new(io::stdout()),
+        )
+    }
+    fn say(&mut self, text: &str) {
+        writeln!(self.output, "{}", text).expect("Output is writable");
+    }
+    // None once the input is closed.
+    fn ask(&mut self, prompt: &str) -> Option<String> {
+        if self.input_closed {
+            return None;
+        }
+        write!(self.output, "{} ", prompt).expect("Output is writable");
+        self.output.flush().expect("Output is writable");
+        let mut line = String::new();
+        let read = self.input.read_line(&mut line).expect("Input is readable");
+        if read == 0 {
+            self.input_closed = true;
+            self.say("\nInput closed, passing from now on.");
+            return None;
+        }
+        Some(line.trim().to_string())
+    }
+    fn ask_yes_no(&mut self, prompt: &str) -> Option<bool> {
+        loop {
+            match self.ask(&format!("{} [y/n]", prompt))?.as_str() {
+                "y" | "yes" => return Some(true),
+                "n" | "no" => return Some(false),
+                _ => self.say("Answer y or n."),
+            }
+        }
+    }
+    // Ask until `parse` accepts the answer and `check` finds it legal,
+    // or the input is closed.
+    fn ask_until<T>(
+        &mut self,
+        prompt: &str,
+        parse: impl Fn(&str) -> Option<T>,
+        check: impl Fn(&T) -> Result<(), IllegalAction>,
+    ) -> Option<T> {
+        loop {
+            let answer = self.ask(prompt)?;
+            match parse(&answer) {
+                None => self.say("Could not read that, try again."),
+                Some(value) => match check(&value) {
+                    Ok(()) => return Some(value),
+                    Err(action) => self.say(&format!("Illegal: that {}.", action)),
+                },
+            }
+        }
+    }
+    fn main_phase_action(&mut self, view: PlayerView, when: &str) -> MainPhaseAction {
+        self.show_view(view);
+        let untapped_lands = view.num_lands - view.tapped_lands;
+        self.say(&format!(
+            "{}, with {} of {} lands untapped.",
+            when, untapped_lands, view.num_lands
+        ));
+        self.ask_until(
+            "Play or cast (hand index, blank to pass):",
+            |answer| parse_action(answer, view.hand),
+            |&action| check_main_phase_action(action, view.hand, untapped_lands, view.played_land),
+        )
+        .unwrap_or(MainPhaseAction::Pass)
+    }
+    fn show_view(&mut self, view: PlayerView) {
+        let text = format!(
+            "Turn {}\n\
+             Opponent: life {}, {} cards in hand, {} in library, {} lands\n\
+             {}\
+             You: life {}, {} in library, {} lands\n\
+             {}\
+             Hand:\n{}",
+            view.num_turn,
+            view.oth_life,
+            view.oth_hand_size,
+            view.oth_deck_size,
+            view.oth_lands,
+            creature_lines(view.oth_creatures, view.num_turn),
+            view.life,
+            view.deck_size,
+            view.num_lands,
+            creature_lines(view.creatures, view.num_turn),
+            hand_lines(view.hand),
+        );
+        self.say(&text);
+    }
+}
+
+// ", flying, reach" or nothing.
+fn keyword_list(keywords: &[Keyword]) -> String {
+    keywords.iter().map(|k| format!(", {}", k)).collect()
+}
+fn creature_lines(creatures: &[Creature], num_turn: u64) -> String {
+    creatures
+        .iter()
+        .enumerate()
+        .map(|(index, creature)| {
+            format!(
+                "  [{}] {} {}/{}{}{}{}\n",
+                index,
+                creature.name(),
+                creature.pow(),
+                creature.tou(),
+                keyword_list(creature.card().keywords()),
+                if creature.tapped {
+                    " (tapped)"
+                } else if creature.summoning_sick(num_turn) {
+                    " (summoning sick)"
+                } else {
+                    ""
+                },
+                if creature.damage() > 0 {
+                    format!(" ({} damage)", creature.damage())
+                } else {
+                    String::new()
+                }
+            )
+        })
+        .collect()
+}
+fn hand_lines(hand: &[Card]) -> String {
+    hand.iter()
+        .enumerate()
+        .map(|(index, card)| match card {
+            Card::Land => format!("  [{}] Land\n", index),
+            Card::Creature(cc) => format!(
+                "  [{}] {} {}/{}/{}{}\n",
+                index,
+                cc.name(),
+                cc.cmc(),
+                cc.pow(),
+                cc.tou(),
+                keyword_list(cc.keywords())
+            ),
+        })
+        .collect()
+}
+// Whitespace-separated indices; empty for none.
+fn parse_indices(answer: &str) -> Option<Vec<usize>> {
+    answer.split_whitespace().map(|i| i.parse().ok()).collect()
+}
+// A hand index plays that card, blank passes.
+fn parse_action(answer: &str, hand: &[Card]) -> Option<MainPhaseAction> {
+    if answer.is_empty() {
+        return Some(MainPhaseAction::Pass);
+    }
+    let index = answer.parse().ok()?;
+    Some(match hand.get(index) {
+        Some(Card::Land) => MainPhaseAction::PlayLand(index),
+        _ => MainPhaseAction::Cast(index),
+    })
+}
+// Amounts in damage order, the last to the player with trample.
+// Blank takes the default.
+fn parse_assignment(
+    answer: &str,
+    trample: bool,
+    default_assignment: &DamageAssignment,
+) -> Option<DamageAssignment> {
+    if answer.is_empty() {
+        return Some(default_assignment.clone());
+    }
+    let mut blockers: Vec<u64> = answer
+        .split_whitespace()
+        .map(|a| a.parse().ok())
+        .collect::<Option<_>>()?;
+    let player = if trample { blockers.pop()? } else { 0 };
+    Some(DamageAssignment { blockers, player })
+}
+// Whitespace-separated `blocker:attacker` pairs.
+fn parse_pairs(answer: &str) -> Option<Vec<(usize, usize)>> {
+    answer
+        .split_whitespace()
+        .map(|pair| {
+            let (blocker, attacker) = pair.split_once(':')?;
+            Some((blocker.parse().ok()?, attacker.parse().ok()?))
+        })
+        .collect()
+}
+
+impl Strategy for Human {
+    fn muligan_choice(&mut self, hand: &[Card], num_muls: usize, is_first: bool) -> MuliganChoice {
+        self.say(&format!(
+            "Opening hand, {} after {} muligans:\n{}",
+            if is_first {
+                "on the play"
+            } else {
+                "on the draw"
+            },
+            num_muls,
+            hand_lines(hand)
+        ));
+        if !self.ask_yes_no("Keep?").unwrap_or(true) {
+            return MuliganChoice::Muligan;
+        }
+        if num_muls == 0 {
+            return MuliganChoice::KeepExcept(vec![]);
+        }
+        let remove = self
+            .ask_until(
+                &format!("Put {} cards on the bottom (hand indices):", num_muls),
+                parse_indices,
+                |remove| check_muligan(remove, num_muls, hand.len()),
+            )
+            .unwrap_or_else(|| (0..num_muls).collect());
+        MuliganChoice::KeepExcept(remove)
+    }
+    fn attack(&mut self, view: PlayerView) -> Vec<usize> {
+        if !(0..view.creatures.len()).any(|i| view.can_attack(i)) {
+            return vec![];
+        }
+        self.show_view(view);
+        self.ask_until(
+            "Attack with (your creature indices, blank for none):",
+            parse_indices,
+            |attackers| check_attackers(attackers, view.creatures, view.num_turn),
+        )
+        .unwrap_or_default()
+    }
+    fn block(&mut self, view: PlayerView, attackers: &[usize]) -> Vec<(usize, usize)> {
+        if attackers.is_empty() || view.creatures.iter().all(|c| c.tapped) {
+            return vec![];
+        }
+        self.show_view(view);
+        self.say(&format!("Opponent attacks with {:?}.", attackers));
+        self.ask_until(
+            "Block with (blocker:attacker pairs, blank for none):",
+            parse_pairs,
+            |blocks| {
+                arrange_blockers(blocks, attackers, view.oth_creatures, view.creatures).map(|_| ())
+            },
+        )
+        .unwrap_or_default()
+    }
+    fn order_blockers(
+        &mut self,
+        view: PlayerView,
+        default_ordering: &HashMap<usize, Vec<usize>>,
+    ) -> HashMap<usize, Vec<usize>> {
+        let mut ordering = default_ordering.clone();
+        let mut attackers: Vec<usize> = default_ordering
+            .iter()
+            .filter(|(_, blockers)| blockers.len() > 1)
+            .map(|(&attacker, _)| attacker)
+            .collect();
+        attackers.sort_unstable();
+        for attacker in attackers {
+            let blockers = &default_ordering[&attacker];
+            self.say(&format!(
+                "Your {} [{}] is blocked by {:?}.",
+                view.creatures[attacker].name(),
+                attacker,
+                blockers
+            ));
+            let expected: HashMap<usize, Vec<usize>> =
+                vec![(attacker, blockers.clone())].into_iter().collect();
+            let order = self.ask_until(
+                "Order its blockers for damage (opponent creature indices):",
+                parse_indices,
+                |order| {
+                    let given = vec![(attacker, order.clone())].into_iter().collect();
+                    check_blocker_order(&expected, &given)
+                },
+            );
+            let order = match order {
+                Some(order) => order,
+                None => break,
+            };
+            ordering.insert(attacker, order);
+        }
+        ordering
+    }
+    fn precombat_main_phase(&mut self, view: PlayerView) -> MainPhaseAction {
+        self.main_phase_action(view, "Before combat")
+    }
+    fn assign_damage(
+        &mut self,
+        view: PlayerView,
+        attacker: usize,
+        blockers: &[usize],
+        default_assignment: &DamageAssignment,
+    ) -> DamageAssignment {
+        let creature = &view.creatures[attacker];
+        let trample = creature.has(Keyword::Trample);
+        let lethal: Vec<u64> = blockers
+            .iter()
+            .map(|&blocker| lethal_damage(creature, &view.oth_creatures[blocker]))
+            .collect();
+        self.say(&format!(
+            "Your {} [{}] deals {} damage{} to blockers {:?}, which need {:?} to die.",
+            creature.name(),
+            attacker,
+            creature.pow(),
+            if trample { " with trample" } else { "" },
+            blockers,
+            lethal
+        ));
+        let prompt = if trample {
+            "Damage to each blocker, then the player (blank for lethal in order):"
+        } else {
+            "Damage to each blocker (blank for lethal in order):"
+        };
+        self.ask_until(
+            prompt,
+            |answer| parse_assignment(answer, trample, default_assignment),
+            |assignment| {
+                check_damage_assignment(assignment, attacker, creature.pow(), trample, &lethal)
+            },
+        )
+        .unwrap_or_else(|| default_assignment.clone())
+    }
+    fn main_phase(&mut self, view: PlayerView) -> MainPhaseAction {
+        self.main_phase_action(view, "After combat")
+    }
+    fn discard(&mut self, view: PlayerView) -> Vec<usize> {
+        self.show_view(view);
+        let count = view.hand.len() - 7;
+        self.ask_until(
+            &format!("Discard {} cards (hand indices):", count),
+            parse_indices,
+            |discards| check_discard(discards, view.hand.len()),
+        )
+        .unwrap_or_else(|| (0..count).collect())
+    }
+}
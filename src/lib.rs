@@ -0,0 +1,19 @@
+//! A simplified Magic: The Gathering simulator where decks contain only
+//! lands and creatures that are vanilla or have only keyword abilities
+//! such as flying, for comparing AI strategies.
+pub mod cards;
+pub mod decklist;
+pub mod events;
+pub mod format;
+pub mod game_data;
+pub mod human;
+pub mod player;
+pub mod replay;
+pub mod runner;
+pub mod tournament;
+#[cfg(feature = "tui")]
+pub mod tui;
+
+mod game;
+
+pub use crate::game::{EndReason, GameOutcome, GameState, PlayerState, Printout, Winner};
@@ -1,731 +1,522 @@
-use std::collections::{HashMap, HashSet};
-
-mod game_data {
-    #[derive(Clone, Debug, PartialEq, Eq)]
-    pub enum Card {
-        Land,
-        Creature(CreatureCard),
-    }
-    #[derive(Clone, Debug, PartialEq, Eq)]
-    pub struct CreatureCard {
-        cmc: u64,
-        pow: u64,
-        tou: u64,
-    }
-    impl CreatureCard {
-        pub fn cmc(&self) -> u64 {
-            self.cmc
-        }
-        #[allow(dead_code)]
-        pub fn pow(&self) -> u64 {
-            self.pow
-        }
-        #[allow(dead_code)]
-        pub fn tou(&self) -> u64 {
-            self.tou
-        }
-    }
-    impl CreatureCard {
-        pub fn try_new(cmc: u64, pow: u64, tou: u64) -> Result<Self, ()> {
-            /* Questionable cards - do they have drawbacks?
-             * Permeating Mass - (1, 1, 3)
-             *   It's a drawback, wrapped in an upside.
-             * Nullhide Ferox - (4, 6, 6)
-             *   Was clearly printed with a drawback,
-             *   but the drawback is irrelevant in this format.
-             */
-            let allowed_cpt = vec![
-                (0, 1, 1), // Memnite
-                (0, 0, 3), // Phyrexian Walker
-                (1, 2, 2), // Icehide Golem; Isamaru, Hound of Konda
-                /* Disowned Ancestor, God-Pharoh's Faithful, Kraken Hatchling,
-                 * Lagonna-Band Trailblazer, Merfolk Secretkeeper,
-                 * Perimeter Captain, Sidisi's Faithful, Steel Wall,
-                 * Tassled Dromedary, Wall of Runes, Yoked Ox
-                 */
-                (1, 0, 4),
-                // Bronzehide Lion, Fleecemane Lion, Kalonian Tusker, Watchwolf
-                (2, 3, 3),
-                (2, 1, 5), // Grizzled Leotau
-                // Dragon's Eye Savants, Fortified Rampart, Wall of Tanglecord
-                (2, 0, 6),
-                (3, 5, 4),    // Steel Leaf Chamption, Wooly Thoctar
-                (3, 4, 5),    // Leatherback Baloth
-                (3, 0, 8),    // Wall of Denial, Wall of Stone
-                (4, 6, 6),    // Nullhide Ferox
-                (4, 2, 10),   // Indominable Ancients
-                (4, 0, 13),   // Tree of Perdition, Tree of Ancients
-                (5, 10, 10),  // Gigantosaurus
-                (9, 11, 9),   // Void Winnower
-                (9, 7, 11),   // Inkwell Leviathan
-                (10, 16, 16), // Impervious Greatwurm
-            ];
-            if allowed_cpt.contains(&(cmc, pow, tou)) {
-                Ok(CreatureCard { cmc, pow, tou })
-            } else {
-                Err(())
-            }
-        }
-    }
-    #[allow(dead_code)]
-    #[derive(Debug)]
-    pub struct Creature {
-        cmc: u64,
-        pow: u64,
-        tou: u64,
-        pub tapped: bool,
-    }
-    impl Creature {
-        pub fn new(creature_card: &CreatureCard) -> Self {
-            Creature {
-                cmc: creature_card.cmc,
-                pow: creature_card.pow,
-                tou: creature_card.tou,
-                tapped: false,
-            }
-        }
-        #[allow(dead_code)]
-        pub fn cmc(&self) -> u64 {
-            self.cmc
-        }
-        pub fn pow(&self) -> u64 {
-            self.pow
-        }
-        pub fn tou(&self) -> u64 {
-            self.tou
-        }
-    }
-    // Either muligan or keep and return cards.
-    #[allow(dead_code)]
-    pub enum MuliganChoice {
-        Muligan,
-        KeepExcept(Vec<usize>),
-    }
-    // The information a player has available
-    pub struct PlayerView<'a> {
-        pub num_turn: u64,
-        pub hand: &'a Vec<Card>,
-        pub num_lands: u64,
-        pub creatures: &'a Vec<Creature>,
-        pub deck_size: usize,
-        pub oth_hand_size: usize,
-        pub oth_lands: u64,
-        pub oth_creatures: &'a Vec<Creature>,
-        pub oth_deck_size: usize,
-    }
-    // Response for main phase:
-    // whether to play a land,
-    // indexes in hand of creatures to play
-    pub struct MainPhasePlays {
-        pub land: bool,
-        pub cards: Vec<usize>,
-    }
-}
+use rand::prelude::*;
+use simple_magic::cards::CARDS;
+use simple_magic::decklist::parse_decklist;
+use simple_magic::events::write_json_lines;
+use simple_magic::format::{find_format, Format, FORMATS};
+use simple_magic::game_data::Card;
+use simple_magic::player::{find_strategy, Strategy, StrategyInfo, STRATEGIES};
+use simple_magic::runner::{play_match, Entrant, MatchConfig, TurnOrder};
+use simple_magic::tournament::play_tournament;
+#[cfg(feature = "tui")]
+use simple_magic::tui;
+use simple_magic::{EndReason, GameState, Printout, Winner};
+use std::fs::{self, File};
+use std::path::Path;
+use std::process;
+use std::thread;
 
-mod player {
-    use crate::game_data::{Card, CreatureCard, MainPhasePlays, MuliganChoice, PlayerView};
-    use std::collections::HashMap;
+// Full turns with no life lost and no creature dying
+// before a game is called a stalemate, unless `--stalemate-turns` says otherwise.
+const DEFAULT_STALEMATE_TURNS: u64 = 20;
+const USAGE: &str = "Usage:
+  simple-magic list
+  simple-magic cards
+  simple-magic formats
+  simple-magic deck <file>...
+  simple-magic play <strategy> <strategy> [--seed N] [--flip]
+                    [--printout nothing|print|pause|tui]
+                    [--events FILE] [--deck1 FILE] [--deck2 FILE]
+  simple-magic match <strategy> <strategy> [--games N] [--seed N] [--flip] [--threads N]
+                     [--deck1 FILE] [--deck2 FILE]
+  simple-magic tournament [<strategy>...] [--games N] [--seed N] [--flip] [--threads N]
+                          [--deck FILE]...
+Strategies play their own decks unless given decklists. A tournament with decks
+enters every strategy once with each deck.
+Every command that plays games also takes:
+  --format NAME        Refuse decks that break the format's rules.
+                       Defaults to AnythingGoes.
+  --turn-limit N       Draw games that reach turn N + 1.
+  --stalemate-turns N  Draw games with no life lost or creature dying for N turns,
+                       or never if N is 0. Defaults to 20.";
 
-    #[derive(Debug)]
-    pub enum Player {
-        LandsSuck,
-        MemnitesDontBlock,
-        LandsRule,
-    }
-    impl Player {
-        // Make a 60 card deck
-        pub fn make_deck(&mut self) -> Vec<Card> {
-            match self {
-                Player::LandsSuck => {
-                    let memnite = CreatureCard::try_new(0, 1, 1).expect("Memnite is allowed");
-                    vec![Card::Creature(memnite); 60]
-                }
-                Player::MemnitesDontBlock => {
-                    let memnite = CreatureCard::try_new(0, 1, 1).expect("Memnite is allowed");
-                    vec![Card::Creature(memnite); 60]
-                }
-                Player::LandsRule => vec![Card::Land; 60],
+struct Options {
+    strategies: Vec<&'static StrategyInfo>,
+    games: u64,
+    seed: u64,
+    flip: bool,
+    printout: Printout,
+    threads: usize,
+    turn_limit: Option<u64>,
+    stalemate_turns: Option<u64>,
+    events: Option<String>,
+    deck1: Option<String>,
+    deck2: Option<String>,
+    decks: Vec<String>,
+    format: &'static Format,
+}
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<&String>) -> Result<T, String> {
+    let value = value.ok_or(format!("{} needs a value", flag))?;
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value for {}: {}", flag, value))
+}
+// The flags each command takes. Those that play games
+// all take `--format`, `--turn-limit` and `--stalemate-turns`.
+const COMMAND_FLAGS: &[(&str, &[&str])] = &[
+    ("list", &[]),
+    ("cards", &[]),
+    ("formats", &[]),
+    (
+        "play",
+        &[
+            "--seed",
+            "--flip",
+            "--printout",
+            "--events",
+            "--deck1",
+            "--deck2",
+            "--format",
+            "--turn-limit",
+            "--stalemate-turns",
+        ],
+    ),
+    (
+        "match",
+        &[
+            "--games",
+            "--seed",
+            "--flip",
+            "--threads",
+            "--deck1",
+            "--deck2",
+            "--format",
+            "--turn-limit",
+            "--stalemate-turns",
+        ],
+    ),
+    (
+        "tournament",
+        &[
+            "--games",
+            "--seed",
+            "--flip",
+            "--threads",
+            "--deck",
+            "--format",
+            "--turn-limit",
+            "--stalemate-turns",
+        ],
+    ),
+];
+fn parse_options(command: &str, args: &[String]) -> Result<Options, String> {
+    let command_flags = COMMAND_FLAGS
+        .iter()
+        .find(|(name, _)| *name == command)
+        .map(|&(_, flags)| flags);
+    let mut options = Options {
+        strategies: vec![],
+        games: 1000,
+        seed: thread_rng().gen(),
+        flip: false,
+        printout: Printout::Print,
+        threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        turn_limit: None,
+        stalemate_turns: Some(DEFAULT_STALEMATE_TURNS),
+        events: None,
+        deck1: None,
+        deck2: None,
+        decks: vec![],
+        format: find_format("AnythingGoes").expect("AnythingGoes is built in"),
+    };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if let Some(flags) = command_flags {
+            let known = COMMAND_FLAGS
+                .iter()
+                .any(|(_, flags)| flags.contains(&arg.as_str()));
+            if known && !flags.contains(&arg.as_str()) {
+                return Err(format!("{} does not take {}", command, arg));
             }
         }
-        pub fn muligan_choice(
-            &mut self,
-            _hand: &Vec<Card>,
-            _num_muls: usize,
-            _is_first: bool,
-        ) -> MuliganChoice {
-            match self {
-                Player::LandsSuck | Player::MemnitesDontBlock | Player::LandsRule => {
-                    MuliganChoice::KeepExcept(vec![])
-                }
+        match arg.as_str() {
+            "--games" => options.games = parse_value(arg, args.next())?,
+            "--seed" => options.seed = parse_value(arg, args.next())?,
+            "--threads" => options.threads = parse_value(arg, args.next())?,
+            "--turn-limit" => options.turn_limit = Some(parse_value(arg, args.next())?),
+            "--stalemate-turns" => {
+                let turns = parse_value(arg, args.next())?;
+                options.stalemate_turns = if turns == 0 { None } else { Some(turns) };
             }
-        }
-        pub fn attack(&mut self, view: PlayerView) -> Vec<usize> {
-            match self {
-                Player::LandsSuck | Player::MemnitesDontBlock => {
-                    (0..view.creatures.len()).collect()
-                }
-                Player::LandsRule => vec![],
+            "--events" => options.events = Some(parse_value(arg, args.next())?),
+            "--deck1" => options.deck1 = Some(parse_value(arg, args.next())?),
+            "--deck2" => options.deck2 = Some(parse_value(arg, args.next())?),
+            "--deck" => options.decks.push(parse_value(arg, args.next())?),
+            "--format" => {
+                let name: String = parse_value(arg, args.next())?;
+                options.format = find_format(&name).ok_or(format!(
+                    "Unknown format {}, see `simple-magic formats`",
+                    name
+                ))?;
             }
-        }
-        pub fn block(&mut self, view: PlayerView, attackers: &Vec<usize>) -> Vec<(usize, usize)> {
-            match self {
-                Player::LandsSuck => {
-                    let mut blockers = vec![];
-                    let mut has_been_blocked = vec![];
-                    let mut num_matched = 0;
-                    let num_available = view.creatures.iter().filter(|c| !c.tapped).count() as u64;
-                    while num_matched < num_available {
-                        let best_block = view
-                            .oth_creatures
-                            .iter()
-                            .enumerate()
-                            .filter(|(i, c)| {
-                                c.tapped
-                                    && c.tou() <= num_available - num_matched
-                                    && !has_been_blocked.contains(i)
-                            })
-                            .max_by_key(|(_, c)| c.tou());
-                        if let Some((best_block_index, best_block_creature)) = best_block {
-                            assert!(attackers.contains(&best_block_index));
-                            let num_block = best_block_creature.tou();
-                            for creature_number in num_matched..num_matched + num_block {
-                                let blocker_index = view
-                                    .creatures
-                                    .iter()
-                                    .enumerate()
-                                    .filter(|(_, c)| !c.tapped)
-                                    .nth(creature_number as usize)
-                                    .expect("Enough blockers available")
-                                    .0;
-                                blockers.push((blocker_index, best_block_index))
-                            }
-                            num_matched += num_block;
-                            has_been_blocked.push(best_block_index);
-                        } else {
-                            break;
-                        }
-                    }
-                    blockers
+            "--flip" => options.flip = true,
+            "--printout" => {
+                options.printout = match args.next().map(|s| s.as_str()) {
+                    Some("nothing") => Printout::Nothing,
+                    Some("print") => Printout::Print,
+                    Some("pause") => Printout::PrintAndPause,
+                    #[cfg(feature = "tui")]
+                    Some("tui") => Printout::Tui,
+                    _ => return Err("--printout must be nothing, print, pause or tui".to_string()),
                 }
-                Player::MemnitesDontBlock | Player::LandsRule => vec![],
             }
-        }
-        pub fn order_blockers(
-            &mut self,
-            view: PlayerView,
-            default_ordering: &HashMap<usize, Vec<usize>>,
-        ) -> HashMap<usize, Vec<usize>> {
-            match self {
-                Player::LandsSuck => {
-                    let mut ordering = HashMap::new();
-                    for (&attacker, blockers) in default_ordering {
-                        let mut blockers = blockers.clone();
-                        blockers.sort_by_key(|&b| view.oth_creatures[b].tou());
-                        ordering.insert(attacker, blockers);
-                    }
-                    ordering
-                }
-                Player::MemnitesDontBlock | Player::LandsRule => default_ordering.clone(),
-            }
-        }
-        pub fn main_phase(&mut self, view: PlayerView) -> MainPhasePlays {
-            match self {
-                Player::LandsSuck | Player::MemnitesDontBlock => MainPhasePlays {
-                    land: false,
-                    cards: (0..view.hand.len()).collect(),
-                },
-                Player::LandsRule => MainPhasePlays {
-                    land: !view.hand.is_empty(),
-                    cards: vec![],
-                },
-            }
-        }
-        pub fn discard(&mut self, view: PlayerView) -> Vec<usize> {
-            assert!(view.hand.len() > 7);
-            match self {
-                Player::LandsSuck | Player::LandsRule | Player::MemnitesDontBlock => {
-                    (0..view.hand.len() - 7).collect()
-                }
+            flag if flag.starts_with("--") => return Err(format!("Unknown option {}", flag)),
+            name => {
+                let info = find_strategy(name).ok_or(format!(
+                    "Unknown strategy {}, see `simple-magic list`",
+                    name
+                ))?;
+                options.strategies.push(info);
             }
         }
     }
+    Ok(options)
 }
-use crate::game_data::{Card, Creature, MainPhasePlays, MuliganChoice, PlayerView};
-use crate::player::Player;
-use rand::prelude::*;
-
-#[derive(Debug)]
-struct PlayerState {
-    player: Player,
-    deck: Vec<Card>,
-    hand: Vec<Card>,
-    num_lands: u64,
-    creatures: Vec<Creature>,
-    life: i64,
-}
-#[derive(Debug, Eq, PartialEq)]
-enum DrawResult {
-    Empty,
-    Nonempty,
-}
-impl PlayerState {
-    fn new(mut player: Player) -> Self {
-        let deck = player.make_deck();
-        assert_eq!(deck.len(), 60);
-        PlayerState {
-            player,
-            deck,
-            hand: vec![],
-            num_lands: 0,
-            creatures: vec![],
-            life: 20,
-        }
-    }
-    fn do_muligans(&mut self, is_first: bool) {
-        let mut rng = thread_rng();
-        let mut num_muls = 0;
-        while num_muls < 7 {
-            self.deck.shuffle(&mut rng);
-            for _ in 0..7 {
-                let draw_result = self.draw();
-                assert_eq!(draw_result, DrawResult::Nonempty);
-            }
-            let perform_muligan = self.player.muligan_choice(&self.hand, num_muls, is_first);
-            if let MuliganChoice::KeepExcept(remove) = perform_muligan {
-                assert_eq!(remove.len(), num_muls);
-                for &index in &remove {
-                    assert!(index < 7);
-                }
-                for i in (0..7).rev() {
-                    if remove.contains(&i) {
-                        let card = self.hand.remove(i);
-                        self.deck.insert(0, card);
-                    }
-                }
-                assert_eq!(self.hand.len(), 7 - num_muls);
-                return;
-            }
-            self.deck.extend(self.hand.drain(..));
-            num_muls += 1;
-        }
-        // If mul down to 0, exit here.
-        assert!(self.hand.is_empty());
+fn two_strategies(options: &Options) -> Result<(&StrategyInfo, &StrategyInfo), String> {
+    match options.strategies.as_slice() {
+        [player1, player2] => Ok((player1, player2)),
+        _ => Err("Expected exactly two strategies".to_string()),
     }
-    fn handle_main_phase_plays(&mut self, main_phase_plays: MainPhasePlays) {
-        if main_phase_plays.land {
-            let land_position = self
-                .hand
-                .iter()
-                .position(|c| c == &Card::Land)
-                .expect("Player tried to play land, so land is present.");
-            self.hand.iter().skip(land_position).for_each(|c| match c {
-                Card::Creature(_) => panic!("Creature after land"),
-                Card::Land => (),
-            });
-            self.hand.remove(land_position);
-            self.num_lands += 1;
-        }
-        let total_cmc: u64 = main_phase_plays
-            .cards
-            .iter()
-            .map(|i| {
-                assert!(*i < self.hand.len());
-                let card = &self.hand[*i];
-                if let Card::Creature(creature_card) = card {
-                    creature_card.cmc()
-                } else {
-                    panic!("Only cast creatures");
-                }
-            })
-            .sum();
-        assert!(total_cmc <= self.num_lands);
-        main_phase_plays.cards.iter().for_each(|i| {
-            let card = &self.hand[*i];
-            if let Card::Creature(creature_card) = card {
-                let creature = Creature::new(creature_card);
-                self.creatures.push(creature);
-            } else {
-                panic!("Only cast creatures");
-            }
-        });
+}
 
-        let prior_number_cards = self.hand.len();
-        let mut index = 0;
-        self.hand.retain(|_| {
-            let keep = !main_phase_plays.cards.contains(&index);
-            index += 1;
-            keep
-        });
-        assert_eq!(
-            prior_number_cards,
-            self.hand.len() + main_phase_plays.cards.len(),
-            "Play correct number of cards"
-        );
+fn list() {
+    let width = STRATEGIES
+        .iter()
+        .map(|info| info.name.len())
+        .max()
+        .unwrap_or(0);
+    for info in STRATEGIES {
+        println!("{:width$}  {}", info.name, info.description, width = width);
     }
-    fn handle_discard(&mut self, discard_indices: Vec<usize>) {
-        assert_eq!(
-            discard_indices.len(),
-            self.hand.len() - 7,
-            "Attempt to discard correct number of cards"
+}
+fn formats() {
+    let width = FORMATS
+        .iter()
+        .map(|format| format.name.len())
+        .max()
+        .unwrap_or(0);
+    for format in FORMATS {
+        println!(
+            "{:width$}  {}",
+            format.name,
+            format.description,
+            width = width
         );
-        let mut index = 0;
-        self.hand.retain(|_| {
-            let keep = !discard_indices.contains(&index);
-            index += 1;
-            keep
-        });
-        assert_eq!(self.hand.len(), 7, "Discard correct number of cards");
     }
-    fn die(&mut self, dead_creatures: Vec<usize>) {
-        let prior_number_creatures = self.creatures.len();
-        let mut index = 0;
-        self.creatures.retain(|_| {
-            let keep = !dead_creatures.contains(&index);
-            index += 1;
-            keep
-        });
-        assert_eq!(
-            prior_number_creatures,
-            self.creatures.len() + dead_creatures.len(),
-            "Correct number of creatures die"
+}
+fn cards() {
+    let width = CARDS.iter().map(|info| info.name.len()).max().unwrap_or(0);
+    let keywords: Vec<String> = CARDS
+        .iter()
+        .map(|info| {
+            let names: Vec<String> = info.keywords.iter().map(|k| k.to_string()).collect();
+            names.join(", ")
+        })
+        .collect();
+    let keywords_width = keywords.iter().map(String::len).max().unwrap_or(0);
+    for (info, keywords) in CARDS.iter().zip(keywords) {
+        let stats = format!("{}/{}/{}", info.cmc, info.pow, info.tou);
+        let line = format!(
+            "{:width$}  {:8}  {:keywords_width$}  {}",
+            info.name,
+            stats,
+            keywords,
+            info.notes,
+            width = width,
+            keywords_width = keywords_width
         );
+        println!("{}", line.trim_end());
     }
-    fn draw(&mut self) -> DrawResult {
-        if self.deck.is_empty() {
-            DrawResult::Empty
-        } else {
-            let card = self.deck.pop().expect("Nonempty");
-            self.hand.push(card);
-            DrawResult::Nonempty
-        }
-    }
-    fn sort_hand(&mut self) {
-        self.hand
-            .sort_by_key(|card| if Card::Land == *card { 1 } else { 0 })
-    }
-    fn untap(&mut self) {
-        for creature in &mut self.creatures {
-            creature.tapped = false
-        }
-    }
-    fn view_and_mut<'a>(
-        &'a mut self,
-        other_state: &'a Self,
-        num_turn: u64,
-    ) -> (PlayerView<'a>, &'a mut Player) {
-        let view = PlayerView {
-            num_turn,
-            hand: &self.hand,
-            num_lands: self.num_lands,
-            creatures: &self.creatures,
-            deck_size: self.deck.len(),
-            oth_hand_size: other_state.hand.len(),
-            oth_lands: other_state.num_lands,
-            oth_creatures: &other_state.creatures,
-            oth_deck_size: other_state.deck.len(),
+}
+// A decklist and its file name without the extension.
+fn load_deck(path: &str) -> Result<(String, Vec<Card>), String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path, e))?;
+    let deck = parse_decklist(&text).map_err(|e| format!("{}: {}", path, e))?;
+    let name = Path::new(path)
+        .file_stem()
+        .map_or(path.to_string(), |stem| stem.to_string_lossy().into_owned());
+    Ok((name, deck))
+}
+// Err listing every violation if `deck` is not legal in `format`.
+fn check_format(format: &Format, name: &str, deck: &[Card]) -> Result<(), String> {
+    format.validate(deck).map_err(|violations| {
+        let violations: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+        format!(
+            "{} is not legal in {}: {}",
+            name,
+            format.name,
+            violations.join("; ")
+        )
+    })
+}
+fn check_entrants(format: &Format, entrants: &[Entrant]) -> Result<(), String> {
+    for entrant in entrants {
+        let deck = match entrant.deck {
+            Some((_, cards)) => cards.to_vec(),
+            None => (entrant.strategy)().make_deck(),
         };
-        (view, &mut self.player)
+        check_format(format, &entrant.name(), &deck)?;
     }
-    fn print_player(&self, is_current_player: bool) {
-        print!(
-            "L: {}, C: {}, P: {:?}",
-            self.life,
-            self.deck.len(),
-            self.player
-        );
-        if is_current_player {
-            print!("   <<<");
-        }
-        println!();
+    Ok(())
+}
+// Print each decklist's size and the formats it is legal in,
+// or the first error in it.
+fn check_decks(paths: &[String]) -> Result<(), String> {
+    if paths.is_empty() {
+        return Err("Expected a decklist file".to_string());
     }
-    fn print_hand(&self) {
-        print!("H: ");
-        for card in &self.hand {
-            match card {
-                Card::Creature(cc) => print!("{}/{}/{} ", cc.cmc(), cc.pow(), cc.tou()),
-                Card::Land => print!("Land "),
+    for path in paths {
+        let (_, deck) = load_deck(path)?;
+        let lands = deck.iter().filter(|&card| card == &Card::Land).count();
+        println!(
+            "{}: {} cards, {} lands, {} creatures",
+            path,
+            deck.len(),
+            lands,
+            deck.len() - lands
+        );
+        for format in FORMATS {
+            match check_format(format, path, &deck) {
+                Ok(()) => println!("  legal in {}", format.name),
+                Err(message) => println!("  {}", message),
             }
         }
-        println!();
     }
-    fn print_battlefield(&self) {
-        print!("B: {} lands    ", self.num_lands);
-        for creature in &self.creatures {
-            print!(
-                "{}/{}/{}{} ",
-                creature.cmc(),
-                creature.pow(),
-                creature.tou(),
-                if creature.tapped { "t" } else { "u" }
-            )
-        }
-        println!();
-    }
-}
-
-#[derive(Debug)]
-enum Winner {
-    Player1,
-    Player2,
+    Ok(())
 }
-#[allow(dead_code)]
-#[derive(Debug, Copy, Clone)]
-enum Printout {
-    PrintAndPause,
-    Print,
-    Nothing,
-}
-#[derive(Debug)]
-struct GameState {
-    player_states: [PlayerState; 2],
-    num_turn: u64,
-    current_player_index: usize,
-    printout: Printout,
-}
-impl GameState {
-    #[allow(dead_code)]
-    fn new_with_flip(player1: Player, player2: Player, printout: Printout) -> Self {
-        let mut rng = thread_rng();
-        let player1_first = rng.gen::<f64>() < 0.5;
-        if player1_first {
-            GameState::new(player1, player2, printout)
-        } else {
-            GameState::new(player2, player1, printout)
-        }
+// People play on the board when there is one.
+fn build_for(info: &StrategyInfo, printout: Printout) -> Box<dyn Strategy> {
+    #[cfg(feature = "tui")]
+    if info.interactive && printout == Printout::Tui {
+        return Box::new(tui::TuiHuman);
     }
-    fn new(player1: Player, player2: Player, printout: Printout) -> Self {
-        GameState {
-            player_states: [PlayerState::new(player1), PlayerState::new(player2)],
-            num_turn: 1,
-            current_player_index: 0,
-            printout,
-        }
+    let _ = printout;
+    (info.build)()
+}
+fn play(options: &Options) -> Result<(), String> {
+    let (player1_info, player2_info) = two_strategies(options)?;
+    let (mut player1, mut player2) = (
+        build_for(player1_info, options.printout),
+        build_for(player2_info, options.printout),
+    );
+    let deck1 = match &options.deck1 {
+        Some(path) => load_deck(path)?.1,
+        None => player1.make_deck(),
+    };
+    let deck2 = match &options.deck2 {
+        Some(path) => load_deck(path)?.1,
+        None => player2.make_deck(),
+    };
+    check_format(options.format, &format!("{:?}'s deck", player1), &deck1)?;
+    check_format(options.format, &format!("{:?}'s deck", player2), &deck2)?;
+    let game = if options.flip {
+        GameState::new_with_flip(
+            player1,
+            deck1,
+            player2,
+            deck2,
+            options.printout,
+            options.seed,
+        )
+    } else {
+        GameState::new(
+            player1,
+            deck1,
+            player2,
+            deck2,
+            options.printout,
+            options.seed,
+        )
+    };
+    let mut game = game
+        .with_turn_limit(options.turn_limit)
+        .with_stalemate_turns(options.stalemate_turns);
+    if options.events.is_some() {
+        game = game.with_event_log();
     }
-    fn play(&mut self) -> Winner {
-        for (i, player_state) in self.player_states.iter_mut().enumerate() {
-            player_state.do_muligans(i == 0);
-        }
-        loop {
-            let num_turn = self.num_turn;
-            let current_player_index = self.current_player_index;
-            // Untap
-            let (current_state, _) = self.states_mut(current_player_index);
-            current_state.untap();
-            if !(num_turn == 1 && current_player_index == 0) {
-                self.handle_printout("Untap");
-            }
-            // Draw step
-            if !(num_turn == 1 && current_player_index == 0) {
-                let draw_result = self.player_states[current_player_index].draw();
-                if let DrawResult::Empty = draw_result {
-                    self.handle_printout("Game over due to decking");
-                    // Game over due to decking
-                    return if current_player_index == 0 {
-                        Winner::Player2
-                    } else {
-                        Winner::Player1
-                    };
-                }
-            }
-            self.player_states[current_player_index].sort_hand();
-            self.handle_printout("Draw");
-            // Current player attacks
-            let (current_state, other_state) = self.states_mut(current_player_index);
-            let (current_view, current_player) = current_state.view_and_mut(&other_state, num_turn);
-            let attackers = current_player.attack(current_view);
-            for &attacker in &attackers {
-                assert!(attacker < current_state.creatures.len());
-                assert!(
-                    !current_state.creatures[attacker].tapped,
-                    "No double attacks"
-                );
-                current_state.creatures[attacker].tapped = true;
-            }
-            if !attackers.is_empty() {
-                self.handle_printout("Attack");
-            }
-            // Other player blocks
-            let (current_state, other_state) = self.states_mut(current_player_index);
-            let (other_view, other_player) = other_state.view_and_mut(&current_state, num_turn);
-            let blocking_pairs = other_player.block(other_view, &attackers);
-            let mut blockers = HashSet::new();
-            let mut blocking_arrangement = HashMap::new();
-            for (blocker, attacker) in blocking_pairs {
-                assert!(attacker < current_state.creatures.len());
-                assert!(blocker < other_state.creatures.len());
-                assert!(
-                    !other_state.creatures[blocker].tapped,
-                    "Tapped creatures can't block"
-                );
-                assert!(!blockers.contains(&blocker), "Creatures can't block twice");
-                blockers.insert(blocker);
-                blocking_arrangement
-                    .entry(attacker)
-                    .or_insert(vec![])
-                    .push(blocker);
-            }
-            // Current player orders blockers
-            let (current_view, current_player) = current_state.view_and_mut(&other_state, num_turn);
-            let ordered_blockers =
-                current_player.order_blockers(current_view, &blocking_arrangement);
-            assert_eq!(
-                blocking_arrangement.len(),
-                ordered_blockers.len(),
-                "Same number of attackers"
-            );
-            for (attacker, blockers) in &ordered_blockers {
-                assert!(blocking_arrangement.contains_key(attacker));
-                let default_blockers = &blocking_arrangement[attacker];
-                assert_eq!(
-                    blockers.len(),
-                    default_blockers.len(),
-                    "Same number of blockers"
-                );
-                blockers
-                    .iter()
-                    .for_each(|i| assert!(default_blockers.contains(i)));
-                default_blockers
-                    .iter()
-                    .for_each(|i| assert!(blockers.contains(i)));
-            }
-            let mut all_blockers = ordered_blockers;
-            // Add in unblocked attackers
-            for &attacker in &attackers {
-                all_blockers.entry(attacker).or_insert(vec![]);
-            }
-            // Damage, check for dead creatures, lethal damage
-            let mut dead_attackers = vec![];
-            let mut dead_blockers = vec![];
-            for (&attacker, blockers) in &all_blockers {
-                let attacker_pow = current_state.creatures[attacker].pow();
-                if blockers.is_empty() {
-                    other_state.life -= attacker_pow as i64
-                } else {
-                    let mut attacker_damage_remaining = attacker_pow;
-                    for &blocker in blockers {
-                        let blocker_tou = other_state.creatures[blocker].tou();
-                        if blocker_tou > attacker_damage_remaining {
-                            break;
-                        } else {
-                            attacker_damage_remaining -= blocker_tou;
-                            dead_blockers.push(blocker)
-                        }
-                    }
-                    let blocker_damage_total: u64 = blockers
-                        .iter()
-                        .map(|&b| other_state.creatures[b].pow())
-                        .sum();
-                    let attacker_tou = current_state.creatures[attacker].tou();
-                    if blocker_damage_total >= attacker_tou {
-                        dead_attackers.push(attacker)
-                    }
-                }
-            }
-            current_state.die(dead_attackers);
-            other_state.die(dead_blockers);
-
-            if other_state.life <= 0 {
-                self.handle_printout("Game over due to life");
-                // Game over due to life loss
-                return if current_player_index == 0 {
-                    Winner::Player1
-                } else {
-                    Winner::Player2
+    let outcome = {
+        #[cfg(feature = "tui")]
+        let _session = match options.printout {
+            Printout::Tui => {
+                // The seat of the one person playing, after any flip
+                let viewer = match (player1_info.interactive, player2_info.interactive) {
+                    (true, false) => Some(if game.flipped() { 1 } else { 0 }),
+                    (false, true) => Some(if game.flipped() { 0 } else { 1 }),
+                    _ => None,
                 };
+                Some(
+                    tui::Session::start(viewer)
+                        .map_err(|e| format!("Could not start the board: {}", e))?,
+                )
             }
-            if !attackers.is_empty() {
-                self.handle_printout("Damage");
-            }
-            // Main phase
-            let (current_state, other_state) = self.states_mut(current_player_index);
-            let (view, player) = current_state.view_and_mut(&other_state, num_turn);
-            let main_phase_plays = player.main_phase(view);
-            current_state.handle_main_phase_plays(main_phase_plays);
-            self.handle_printout("Main phase");
-
-            // Discard
-            let (current_state, other_state) = self.states_mut(current_player_index);
-            if current_state.hand.len() > 7 {
-                let (view, player) = current_state.view_and_mut(&other_state, num_turn);
-                let discard_indices = player.discard(view);
-                current_state.handle_discard(discard_indices);
-                self.handle_printout("Discard");
-            }
-            // Switch current player, increment turn number as appropriate
-            self.current_player_index = 1 - self.current_player_index;
-            if self.current_player_index == 0 {
-                self.num_turn += 1;
-            }
-        }
+            _ => None,
+        };
+        game.play()
+    };
+    if let Some(path) = &options.events {
+        File::create(path)
+            .and_then(|mut file| write_json_lines(game.events(), &mut file))
+            .map_err(|e| format!("Could not write {}: {}", path, e))?;
     }
-    fn states_mut(&mut self, current_player: usize) -> (&mut PlayerState, &mut PlayerState) {
-        let (first_state, rest) = self
-            .player_states
-            .split_first_mut()
-            .expect("Multiple players");
-        let second_state = &mut rest[0];
-        if current_player == 0 {
-            (first_state, second_state)
-        } else {
-            (second_state, first_state)
-        }
+    let player1 = game.player_state(0).strategy();
+    let player2 = game.player_state(1).strategy();
+    let winner_index = match outcome.winner {
+        Winner::Player1 => Some(0),
+        Winner::Player2 => Some(1),
+        Winner::Draw => None,
+    };
+    match winner_index {
+        Some(index) => println!(
+            "{:?} v {:?}: {:?} ({}) wins by {:?}",
+            player1,
+            player2,
+            game.player_state(index).strategy(),
+            index,
+            outcome.reason
+        ),
+        None => println!(
+            "{:?} v {:?}: draw by {:?}",
+            player1, player2, outcome.reason
+        ),
     }
-    fn handle_printout(&self, phase: &str) {
-        if let Printout::Nothing = self.printout {
-            return;
-        }
-        println!("{} {}", phase, self.num_turn);
-        let state0 = &self.player_states[0];
-        let state1 = &self.player_states[1];
-        state0.print_player(self.current_player_index == 0);
-        state0.print_hand();
-        state0.print_battlefield();
-        state1.print_battlefield();
-        state1.print_hand();
-        state1.print_player(self.current_player_index == 1);
-
-        if let Printout::PrintAndPause = self.printout {
-            use std::io::{stdin, stdout, Write};
-            let mut s = String::new();
-            println!("Enter to continue");
-            stdout().flush().expect("Flushed");
-            stdin().read_line(&mut s).expect("Continued");
-        }
+    if let EndReason::Forfeit(action) = outcome.reason {
+        println!("Illegal action: {}", action);
+    }
+    println!(
+        "On turn {} of {:?} ({}), life {} v {}",
+        game.num_turn(),
+        game.player_state(game.current_player_index()).strategy(),
+        game.current_player_index(),
+        game.player_state(0).life(),
+        game.player_state(1).life()
+    );
+    println!("Seed {}", game.seed());
+    Ok(())
+}
+// Interactive strategies cannot sit through many games on worker threads.
+fn check_not_interactive(strategies: &[&StrategyInfo]) -> Result<(), String> {
+    match strategies.iter().find(|info| info.interactive) {
+        Some(info) => Err(format!("{} can only play single games", info.name)),
+        None => Ok(()),
     }
 }
+fn run_match(options: &Options) -> Result<(), String> {
+    let (player1, player2) = two_strategies(options)?;
+    check_not_interactive(&[player1, player2])?;
+    let decks = [
+        options.deck1.as_deref().map(load_deck).transpose()?,
+        options.deck2.as_deref().map(load_deck).transpose()?,
+    ];
+    let entrants: Vec<Entrant> = [player1, player2]
+        .iter()
+        .zip(&decks)
+        .map(|(info, deck)| match deck {
+            Some((name, cards)) => Entrant::new(&info.build).with_deck(name, cards),
+            None => Entrant::new(&info.build),
+        })
+        .collect();
+    check_entrants(options.format, &entrants)?;
+    let config = MatchConfig {
+        num_games: options.games,
+        seed: options.seed,
+        turn_order: if options.flip {
+            TurnOrder::Flip
+        } else {
+            TurnOrder::Alternate
+        },
+        threads: options.threads,
+        turn_limit: options.turn_limit,
+        stalemate_turns: options.stalemate_turns,
+    };
+    let result = play_match(&entrants[0], &entrants[1], &config);
+    println!("{}", result);
+    println!("Seed {}", options.seed);
+    Ok(())
+}
+fn tournament(options: &Options) -> Result<(), String> {
+    let strategies: Vec<&StrategyInfo> = if options.strategies.is_empty() {
+        STRATEGIES.iter().filter(|info| !info.interactive).collect()
+    } else {
+        options.strategies.clone()
+    };
+    check_not_interactive(&strategies)?;
+    let decks = options
+        .decks
+        .iter()
+        .map(|path| load_deck(path))
+        .collect::<Result<Vec<_>, _>>()?;
+    let entrants: Vec<Entrant> = if decks.is_empty() {
+        strategies
+            .iter()
+            .map(|info| Entrant::new(&info.build))
+            .collect()
+    } else {
+        strategies
+            .iter()
+            .flat_map(|info| {
+                decks
+                    .iter()
+                    .map(move |(name, cards)| Entrant::new(&info.build).with_deck(name, cards))
+            })
+            .collect()
+    };
+    check_entrants(options.format, &entrants)?;
+    let config = MatchConfig {
+        num_games: options.games,
+        seed: options.seed,
+        turn_order: if options.flip {
+            TurnOrder::Flip
+        } else {
+            TurnOrder::Fixed
+        },
+        threads: options.threads,
+        turn_limit: options.turn_limit,
+        stalemate_turns: options.stalemate_turns,
+    };
+    let result = play_tournament(&entrants, &config);
+    println!("{}", result);
+    println!("Seed {}", options.seed);
+    Ok(())
+}
+
 fn main() {
-    for (player1, player2) in vec![
-        (Player::LandsRule, Player::LandsRule),
-        (Player::LandsRule, Player::LandsSuck),
-        (Player::LandsSuck, Player::LandsSuck),
-        (Player::LandsSuck, Player::MemnitesDontBlock),
-        (Player::MemnitesDontBlock, Player::LandsSuck),
-        (Player::MemnitesDontBlock, Player::MemnitesDontBlock),
-    ] {
-        let mut game = GameState::new(player1, player2, Printout::Nothing);
-        let winner = game.play();
-        let player1 = &game.player_states[0].player;
-        let player2 = &game.player_states[1].player;
-        println!(
-            "{:?} v {:?}: {:?} ({}) wins",
-            player1, player2,
-            match winner {
-                Winner::Player1 => player1,
-                Winner::Player2 => player2,
-            },
-            match winner {
-                Winner::Player1 => 0,
-                Winner::Player2 => 1,
-            }
-        );
-        println!(
-            "On turn {} of {:?} ({}), life {} v {}",
-            game.num_turn,
-            game.player_states[game.current_player_index].player,
-            game.current_player_index,
-            game.player_states[0].life,
-            game.player_states[1].life
-        );
-        println!()
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.split_first() {
+        Some((command, rest)) if command == "deck" => check_decks(rest),
+        Some((command, rest)) => {
+            parse_options(command, rest).and_then(|options| match command.as_str() {
+                "list" => {
+                    list();
+                    Ok(())
+                }
+                "cards" => {
+                    cards();
+                    Ok(())
+                }
+                "formats" => {
+                    formats();
+                    Ok(())
+                }
+                "play" => play(&options),
+                "match" => run_match(&options),
+                "tournament" => tournament(&options),
+                _ => Err(format!("Unknown command {}", command)),
+            })
+        }
+        None => Err("Expected a command".to_string()),
+    };
+    if let Err(message) = result {
+        eprintln!("{}", message);
+        eprintln!("{}", USAGE);
+        process::exit(2);
     }
 }
@@ -0,0 +1,243 @@
+use crate::cards::Keyword;
+use crate::game_data::{
+    Card, Creature, CreatureCard, DamageAssignment, MainPhaseAction, MuliganChoice, PlayerView,
+};
+use crate::human::Human;
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+/// A decision-maker for one seat of the game.
+/// Only attacking and the main phase are required;
+/// the remaining decisions default to the simplest legal choice.
+pub trait Strategy: Debug {
+    /// The deck this strategy plays when not given one.
+    fn make_deck(&mut self) -> Vec<Card> {
+        default_deck()
+    }
+    fn muligan_choice(
+        &mut self,
+        _hand: &[Card],
+        _num_muls: usize,
+        _is_first: bool,
+    ) -> MuliganChoice {
+        MuliganChoice::KeepExcept(vec![])
+    }
+    fn attack(&mut self, view: PlayerView) -> Vec<usize>;
+    /// Pairs of (blocker, attacker)
+    fn block(&mut self, _view: PlayerView, _attackers: &[usize]) -> Vec<(usize, usize)> {
+        vec![]
+    }
+    fn order_blockers(
+        &mut self,
+        _view: PlayerView,
+        default_ordering: &HashMap<usize, Vec<usize>>,
+    ) -> HashMap<usize, Vec<usize>> {
+        default_ordering.clone()
+    }
+    /// How your `attacker` splits its damage among `blockers`, which are
+    /// the opponent's creatures still blocking it, in damage order.
+    /// Only asked when there is a choice: several blockers, or trample.
+    /// Creatures killed in the first-strike step stay in the view
+    /// until combat ends. Takes `default_assignment` unless overridden.
+    fn assign_damage(
+        &mut self,
+        _view: PlayerView,
+        _attacker: usize,
+        _blockers: &[usize],
+        default_assignment: &DamageAssignment,
+    ) -> DamageAssignment {
+        default_assignment.clone()
+    }
+    /// One action in the main phase before combat, asked again
+    /// with an updated view until it passes. Passes unless overridden.
+    fn precombat_main_phase(&mut self, _view: PlayerView) -> MainPhaseAction {
+        MainPhaseAction::Pass
+    }
+    /// One action in the main phase after combat, asked again
+    /// with an updated view until it passes.
+    fn main_phase(&mut self, view: PlayerView) -> MainPhaseAction;
+    fn discard(&mut self, view: PlayerView) -> Vec<usize> {
+        assert!(view.hand.len() > 7);
+        (0..view.hand.len() - 7).collect()
+    }
+}
+
+/// A named strategy that can be built on demand, e.g. from the command line.
+#[derive(Debug, Clone, Copy)]
+pub struct StrategyInfo {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub build: fn() -> Box<dyn Strategy>,
+    /// Asks a person for decisions, so it is only fit for single games.
+    pub interactive: bool,
+}
+pub static STRATEGIES: &[StrategyInfo] = &[
+    StrategyInfo {
+        name: "LandsSuck",
+        description: "All Memnites, always attacks, blocks to kill attackers, casts what fits.",
+        build: || Box::new(LandsSuck),
+        interactive: false,
+    },
+    StrategyInfo {
+        name: "MemnitesDontBlock",
+        description: "All Memnites, always attacks, never blocks, casts what fits.",
+        build: || Box::new(MemnitesDontBlock),
+        interactive: false,
+    },
+    StrategyInfo {
+        name: "LandsRule",
+        description: "All lands, plays one every turn and does nothing else.",
+        build: || Box::new(LandsRule),
+        interactive: false,
+    },
+    StrategyInfo {
+        name: "Human",
+        description: "You, at the terminal, with the default deck.",
+        build: || Box::new(Human::stdio()),
+        interactive: true,
+    },
+];
+/// Look up a built-in strategy by name, ignoring case.
+pub fn find_strategy(name: &str) -> Option<&'static StrategyInfo> {
+    STRATEGIES
+        .iter()
+        .find(|info| info.name.eq_ignore_ascii_case(name))
+}
+
+/// 24 lands and a curve of 1, 2 and 3 drops.
+pub fn default_deck() -> Vec<Card> {
+    let mut deck = vec![Card::Land; 24];
+    for name in &["Icehide Golem", "Watchwolf", "Steel Leaf Champion"] {
+        let creature = CreatureCard::named(name).expect("Curve is in the registry");
+        deck.extend(vec![Card::Creature(creature); 12]);
+    }
+    deck
+}
+fn memnite_deck() -> Vec<Card> {
+    let memnite = CreatureCard::named("Memnite").expect("Memnite is in the registry");
+    vec![Card::Creature(memnite); 60]
+}
+// Play a land if there is one, then cast creatures in hand order
+// while there is mana for them.
+fn cast_what_fits(view: PlayerView) -> MainPhaseAction {
+    if let Some(index) = land_to_play(view) {
+        return MainPhaseAction::PlayLand(index);
+    }
+    let untapped_lands = view.num_lands - view.tapped_lands;
+    view.hand
+        .iter()
+        .position(|card| matches!(card, Card::Creature(cc) if cc.cmc() <= untapped_lands))
+        .map_or(MainPhaseAction::Pass, MainPhaseAction::Cast)
+}
+// The hand index of a land, if one can be played.
+fn land_to_play(view: PlayerView) -> Option<usize> {
+    if view.played_land {
+        return None;
+    }
+    view.hand.iter().position(|card| card == &Card::Land)
+}
+
+/// All Memnites, always attacks, blocks to kill attackers, casts what fits.
+#[derive(Debug)]
+pub struct LandsSuck;
+impl Strategy for LandsSuck {
+    fn make_deck(&mut self) -> Vec<Card> {
+        memnite_deck()
+    }
+    fn attack(&mut self, view: PlayerView) -> Vec<usize> {
+        (0..view.creatures.len())
+            .filter(|&i| view.can_attack(i))
+            .collect()
+    }
+    fn block(&mut self, view: PlayerView, attackers: &[usize]) -> Vec<(usize, usize)> {
+        let mut blockers = vec![];
+        let mut has_been_blocked = vec![];
+        let mut available: Vec<usize> = (0..view.creatures.len())
+            .filter(|&i| !view.creatures[i].tapped)
+            .collect();
+        // Enough blockers to deal lethal damage, assuming they are Memnites.
+        let needed = |attacker: &Creature| {
+            if attacker.has(Keyword::Menace) {
+                attacker.tou().max(2)
+            } else {
+                attacker.tou()
+            }
+        };
+        loop {
+            let best_block = view
+                .oth_creatures
+                .iter()
+                .enumerate()
+                .filter(|(i, c)| {
+                    let able = available.iter().filter(|&&b| view.can_block(b, *i)).count();
+                    attackers.contains(i)
+                        && needed(c) <= able as u64
+                        && !has_been_blocked.contains(i)
+                })
+                .max_by_key(|(_, c)| c.tou());
+            if let Some((best_block_index, best_block_creature)) = best_block {
+                let num_block = needed(best_block_creature) as usize;
+                let chosen: Vec<usize> = available
+                    .iter()
+                    .copied()
+                    .filter(|&b| view.can_block(b, best_block_index))
+                    .take(num_block)
+                    .collect();
+                available.retain(|b| !chosen.contains(b));
+                blockers.extend(chosen.into_iter().map(|b| (b, best_block_index)));
+                has_been_blocked.push(best_block_index);
+            } else {
+                break;
+            }
+        }
+        blockers
+    }
+    fn order_blockers(
+        &mut self,
+        view: PlayerView,
+        default_ordering: &HashMap<usize, Vec<usize>>,
+    ) -> HashMap<usize, Vec<usize>> {
+        let mut ordering = HashMap::new();
+        for (&attacker, blockers) in default_ordering {
+            let mut blockers = blockers.clone();
+            blockers.sort_by_key(|&b| view.oth_creatures[b].tou());
+            ordering.insert(attacker, blockers);
+        }
+        ordering
+    }
+    fn main_phase(&mut self, view: PlayerView) -> MainPhaseAction {
+        cast_what_fits(view)
+    }
+}
+
+/// All Memnites, always attacks, never blocks, casts what fits.
+#[derive(Debug)]
+pub struct MemnitesDontBlock;
+impl Strategy for MemnitesDontBlock {
+    fn make_deck(&mut self) -> Vec<Card> {
+        memnite_deck()
+    }
+    fn attack(&mut self, view: PlayerView) -> Vec<usize> {
+        (0..view.creatures.len())
+            .filter(|&i| view.can_attack(i))
+            .collect()
+    }
+    fn main_phase(&mut self, view: PlayerView) -> MainPhaseAction {
+        cast_what_fits(view)
+    }
+}
+
+/// All lands, plays one every turn and does nothing else.
+#[derive(Debug)]
+pub struct LandsRule;
+impl Strategy for LandsRule {
+    fn make_deck(&mut self) -> Vec<Card> {
+        vec![Card::Land; 60]
+    }
+    fn attack(&mut self, _view: PlayerView) -> Vec<usize> {
+        vec![]
+    }
+    fn main_phase(&mut self, view: PlayerView) -> MainPhaseAction {
+        land_to_play(view).map_or(MainPhaseAction::Pass, MainPhaseAction::PlayLand)
+    }
+}
@@ -0,0 +1,239 @@
+use crate::game::{GameState, Printout};
+use crate::game_data::{Card, DamageAssignment, MainPhaseAction, MuliganChoice, PlayerView};
+use crate::player::Strategy;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+
+/// The return value of one call to a `Strategy` method.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Decision {
+    Muligan(MuliganChoice),
+    Attack(Vec<usize>),
+    Block(Vec<(usize, usize)>),
+    OrderBlockers(HashMap<usize, Vec<usize>>),
+    AssignDamage(DamageAssignment),
+    PrecombatMainPhase(MainPhaseAction),
+    MainPhase(MainPhaseAction),
+    Discard(Vec<usize>),
+}
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecordedDecision {
+    /// Seat of the player who made the decision.
+    pub player: usize,
+    /// The turn it was made in, 0 for muligans.
+    pub num_turn: u64,
+    /// Seat of the player whose turn it was.
+    pub active_player: usize,
+    pub decision: Decision,
+}
+/// Everything needed to replay a game exactly:
+/// its seed and settings, both decks before shuffling,
+/// and every decision either strategy made.
+#[derive(Clone, Debug)]
+pub struct Replay {
+    pub seed: u64,
+    /// Whether the game was built with `GameState::new_with_flip`.
+    pub coin_flipped: bool,
+    /// Whether that coin flip put `player2` in the first seat.
+    pub flipped: bool,
+    pub turn_limit: Option<u64>,
+    pub stalemate_turns: Option<u64>,
+    /// Indexed by seat.
+    pub decks: [Vec<Card>; 2],
+    pub decisions: Vec<RecordedDecision>,
+}
+impl Replay {
+    /// Rebuild the game as it stood at the start of `player`'s turn `num_turn`
+    /// (or at its end, if it ended sooner). Games can only be rebuilt
+    /// between turns, not partway through one. Indexed by seat, `strategies`
+    /// make every decision from that point on, so the game can be resumed
+    /// with `play` using the original strategies or different ones.
+    /// Stateful strategies are not told about the replayed decisions.
+    /// The rebuilt game keeps its own decision log.
+    pub fn rebuild(
+        &self,
+        num_turn: u64,
+        player: usize,
+        strategies: [Box<dyn Strategy>; 2],
+        printout: Printout,
+    ) -> GameState {
+        let [strategy0, strategy1] = strategies;
+        let replaying = |seat: usize, then: Box<dyn Strategy>| -> Box<dyn Strategy> {
+            let decisions = self
+                .decisions
+                .iter()
+                .filter(|d| d.player == seat && (d.num_turn, d.active_player) < (num_turn, player))
+                .map(|d| d.decision.clone())
+                .collect();
+            Box::new(Replaying { decisions, then })
+        };
+        GameState::rebuild(
+            self,
+            replaying(0, strategy0),
+            replaying(1, strategy1),
+            printout,
+            num_turn,
+            player,
+        )
+    }
+}
+
+// Returns recorded decisions in order, then hands over to `then`.
+struct Replaying {
+    decisions: VecDeque<Decision>,
+    then: Box<dyn Strategy>,
+}
+impl fmt::Debug for Replaying {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.then)
+    }
+}
+impl Replaying {
+    fn next(&mut self) -> Option<Decision> {
+        self.decisions.pop_front()
+    }
+}
+fn diverged(decision: Decision) -> ! {
+    panic!("Replay diverged from the recorded game at {:?}", decision)
+}
+impl Strategy for Replaying {
+    fn muligan_choice(&mut self, hand: &[Card], num_muls: usize, is_first: bool) -> MuliganChoice {
+        match self.next() {
+            Some(Decision::Muligan(choice)) => choice,
+            Some(decision) => diverged(decision),
+            None => self.then.muligan_choice(hand, num_muls, is_first),
+        }
+    }
+    fn attack(&mut self, view: PlayerView) -> Vec<usize> {
+        match self.next() {
+            Some(Decision::Attack(attackers)) => attackers,
+            Some(decision) => diverged(decision),
+            None => self.then.attack(view),
+        }
+    }
+    fn block(&mut self, view: PlayerView, attackers: &[usize]) -> Vec<(usize, usize)> {
+        match self.next() {
+            Some(Decision::Block(blocks)) => blocks,
+            Some(decision) => diverged(decision),
+            None => self.then.block(view, attackers),
+        }
+    }
+    fn order_blockers(
+        &mut self,
+        view: PlayerView,
+        default_ordering: &HashMap<usize, Vec<usize>>,
+    ) -> HashMap<usize, Vec<usize>> {
+        match self.next() {
+            Some(Decision::OrderBlockers(ordering)) => ordering,
+            Some(decision) => diverged(decision),
+            None => self.then.order_blockers(view, default_ordering),
+        }
+    }
+    fn assign_damage(
+        &mut self,
+        view: PlayerView,
+        attacker: usize,
+        blockers: &[usize],
+        default_assignment: &DamageAssignment,
+    ) -> DamageAssignment {
+        match self.next() {
+            Some(Decision::AssignDamage(assignment)) => assignment,
+            Some(decision) => diverged(decision),
+            None => self
+                .then
+                .assign_damage(view, attacker, blockers, default_assignment),
+        }
+    }
+    fn precombat_main_phase(&mut self, view: PlayerView) -> MainPhaseAction {
+        match self.next() {
+            Some(Decision::PrecombatMainPhase(action)) => action,
+            Some(decision) => diverged(decision),
+            None => self.then.precombat_main_phase(view),
+        }
+    }
+    fn main_phase(&mut self, view: PlayerView) -> MainPhaseAction {
+        match self.next() {
+            Some(Decision::MainPhase(action)) => action,
+            Some(decision) => diverged(decision),
+            None => self.then.main_phase(view),
+        }
+    }
+    fn discard(&mut self, view: PlayerView) -> Vec<usize> {
+        match self.next() {
+            Some(Decision::Discard(discards)) => discards,
+            Some(decision) => diverged(decision),
+            None => self.then.discard(view),
+        }
+    }
+}
+
+// Collects decisions only when enabled, like `EventLog`.
+#[derive(Debug, Default)]
+pub(crate) struct DecisionLog {
+    decisions: Option<Vec<RecordedDecision>>,
+}
+impl DecisionLog {
+    pub(crate) fn enable(&mut self) {
+        self.decisions.get_or_insert_with(Vec::new);
+    }
+    pub(crate) fn record(
+        &mut self,
+        player: usize,
+        num_turn: u64,
+        active_player: usize,
+        decision: impl FnOnce() -> Decision,
+    ) {
+        if let Some(decisions) = &mut self.decisions {
+            decisions.push(RecordedDecision {
+                player,
+                num_turn,
+                active_player,
+                decision: decision(),
+            });
+        }
+    }
+    pub(crate) fn decisions(&self) -> Option<&[RecordedDecision]> {
+        self.decisions.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::{LandsSuck, MemnitesDontBlock};
+    use crate::runner::{self, Entrant, MatchConfig, TurnOrder};
+
+    #[test]
+    fn rebuilt_games_play_out_the_same() {
+        let lands_suck = || Box::new(LandsSuck) as Box<dyn Strategy>;
+        let memnites = || Box::new(MemnitesDontBlock) as Box<dyn Strategy>;
+        let entrants = [Entrant::new(&lands_suck), Entrant::new(&memnites)];
+        let config = MatchConfig {
+            num_games: 10,
+            seed: 3,
+            turn_order: TurnOrder::Flip,
+            threads: 1,
+            turn_limit: None,
+            stalemate_turns: None,
+        };
+        for game_index in 0..config.num_games {
+            let (game, first) =
+                runner::replay_game(&entrants[0], &entrants[1], &config, game_index);
+            let outcome = game.outcome().expect("Played out");
+            let replay = game.replay().expect("Logged");
+            for &(num_turn, player) in &[(0, 0), (1, 1), (3, 0), (game.num_turn(), 1)] {
+                let strategies = [
+                    (entrants[first].strategy)(),
+                    (entrants[1 - first].strategy)(),
+                ];
+                let mut rebuilt = replay.rebuild(num_turn, player, strategies, Printout::Nothing);
+                assert_eq!(rebuilt.play(), outcome);
+                assert_eq!(rebuilt.num_turn(), game.num_turn());
+                assert_eq!(
+                    rebuilt.replay().expect("Logged").decisions,
+                    replay.decisions
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,424 @@
+use crate::game::{EndReason, GameState, Printout, Winner};
+use crate::game_data::{Card, IllegalAction};
+use crate::player::Strategy;
+use std::fmt;
+use std::thread;
+
+/// A strategy, built fresh for every game, and the deck it plays.
+#[derive(Clone, Copy)]
+pub struct Entrant<'a> {
+    pub strategy: &'a (dyn Fn() -> Box<dyn Strategy> + Sync),
+    /// A deck name and its cards; `None` plays the strategy's own `make_deck`.
+    pub deck: Option<(&'a str, &'a [Card])>,
+}
+impl<'a> Entrant<'a> {
+    pub fn new(strategy: &'a (dyn Fn() -> Box<dyn Strategy> + Sync)) -> Self {
+        Entrant {
+            strategy,
+            deck: None,
+        }
+    }
+    pub fn with_deck(mut self, name: &'a str, cards: &'a [Card]) -> Self {
+        self.deck = Some((name, cards));
+        self
+    }
+    /// The strategy's name, and the deck's if it was given one.
+    pub fn name(&self) -> String {
+        let strategy = (self.strategy)();
+        match self.deck {
+            Some((deck_name, _)) => format!("{:?} with {}", strategy, deck_name),
+            None => format!("{:?}", strategy),
+        }
+    }
+    fn build(&self) -> (Box<dyn Strategy>, Vec<Card>) {
+        let mut strategy = (self.strategy)();
+        let deck = match self.deck {
+            Some((_, cards)) => cards.to_vec(),
+            None => strategy.make_deck(),
+        };
+        (strategy, deck)
+    }
+}
+/// How to decide who goes first in each game of a match.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TurnOrder {
+    /// The first entrant goes first in even-numbered games.
+    Alternate,
+    /// Each game flips a coin with `GameState::new_with_flip`.
+    Flip,
+    /// The first entrant always goes first.
+    Fixed,
+}
+#[derive(Debug, Copy, Clone)]
+pub struct MatchConfig {
+    pub num_games: u64,
+    /// Game `i` is played with seed `seed + i`.
+    pub seed: u64,
+    pub turn_order: TurnOrder,
+    /// Number of worker threads; 0 is treated as 1.
+    pub threads: usize,
+    /// Passed to `GameState::with_turn_limit`.
+    pub turn_limit: Option<u64>,
+    /// Passed to `GameState::with_stalemate_turns`.
+    pub stalemate_turns: Option<u64>,
+}
+/// The first game of a match lost to an illegal action.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Forfeit {
+    pub game_index: u64,
+    pub seed: u64,
+    /// Index of the entrant that broke the rules.
+    pub offender: usize,
+    pub action: IllegalAction,
+}
+/// Totals over a match. Arrays are indexed by entrant:
+/// 0 for the first entrant passed to `play_match`, 1 for the second.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchResult {
+    pub names: [String; 2],
+    pub num_games: u64,
+    pub wins: [u64; 2],
+    pub games_on_play: [u64; 2],
+    pub wins_on_play: [u64; 2],
+    pub draws: u64,
+    pub total_turns: u64,
+    pub ended_by_life: u64,
+    pub ended_by_decking: u64,
+    pub ended_by_turn_limit: u64,
+    pub ended_by_stalemate: u64,
+    pub ended_by_forfeit: u64,
+    pub first_forfeit: Option<Forfeit>,
+}
+impl MatchResult {
+    fn new(names: [String; 2]) -> Self {
+        MatchResult {
+            names,
+            num_games: 0,
+            wins: [0; 2],
+            games_on_play: [0; 2],
+            wins_on_play: [0; 2],
+            draws: 0,
+            total_turns: 0,
+            ended_by_life: 0,
+            ended_by_decking: 0,
+            ended_by_turn_limit: 0,
+            ended_by_stalemate: 0,
+            ended_by_forfeit: 0,
+            first_forfeit: None,
+        }
+    }
+    fn record(&mut self, game: GameRecord) {
+        self.num_games += 1;
+        self.games_on_play[game.first] += 1;
+        match game.winner {
+            Some(winner) => {
+                self.wins[winner] += 1;
+                if winner == game.first {
+                    self.wins_on_play[winner] += 1;
+                }
+            }
+            None => self.draws += 1,
+        }
+        self.total_turns += game.num_turn;
+        match game.reason {
+            EndReason::Life => self.ended_by_life += 1,
+            EndReason::Decking => self.ended_by_decking += 1,
+            EndReason::TurnLimit => self.ended_by_turn_limit += 1,
+            EndReason::Stalemate => self.ended_by_stalemate += 1,
+            EndReason::Forfeit(action) => {
+                self.ended_by_forfeit += 1;
+                if self.first_forfeit.is_none() {
+                    self.first_forfeit = Some(Forfeit {
+                        game_index: game.game_index,
+                        seed: game.seed,
+                        offender: 1 - game.winner.expect("Forfeits have a winner"),
+                        action,
+                    });
+                }
+            }
+        }
+    }
+    fn merge(&mut self, other: &MatchResult) {
+        self.num_games += other.num_games;
+        for index in 0..2 {
+            self.wins[index] += other.wins[index];
+            self.games_on_play[index] += other.games_on_play[index];
+            self.wins_on_play[index] += other.wins_on_play[index];
+        }
+        self.draws += other.draws;
+        self.total_turns += other.total_turns;
+        self.ended_by_life += other.ended_by_life;
+        self.ended_by_decking += other.ended_by_decking;
+        self.ended_by_turn_limit += other.ended_by_turn_limit;
+        self.ended_by_stalemate += other.ended_by_stalemate;
+        self.ended_by_forfeit += other.ended_by_forfeit;
+        self.first_forfeit = match (self.first_forfeit, other.first_forfeit) {
+            (Some(mine), Some(theirs)) if theirs.game_index < mine.game_index => Some(theirs),
+            (None, theirs) => theirs,
+            (mine, _) => mine,
+        };
+    }
+    /// Zero for a match of no games.
+    pub fn win_rate(&self, index: usize) -> f64 {
+        if self.num_games == 0 {
+            return 0.0;
+        }
+        self.wins[index] as f64 / self.num_games as f64
+    }
+    /// 95% Wilson score interval for the win rate,
+    /// the whole range for a match of no games.
+    pub fn confidence_interval(&self, index: usize) -> (f64, f64) {
+        if self.num_games == 0 {
+            return (0.0, 1.0);
+        }
+        let z = 1.96;
+        let n = self.num_games as f64;
+        let p = self.win_rate(index);
+        let denominator = 1.0 + z * z / n;
+        let center = (p + z * z / (2.0 * n)) / denominator;
+        let half_width = z / denominator * (p * (1.0 - p) / n + z * z / (4.0 * n * n)).sqrt();
+        (
+            (center - half_width).max(0.0),
+            (center + half_width).min(1.0),
+        )
+    }
+    /// The win rate in games the entrant went first,
+    /// zero if it never did.
+    pub fn win_rate_on_play(&self, index: usize) -> f64 {
+        if self.games_on_play[index] == 0 {
+            return 0.0;
+        }
+        self.wins_on_play[index] as f64 / self.games_on_play[index] as f64
+    }
+    /// Wins when going second. Draws in this sense are
+    /// play/draw, not drawn games.
+    pub fn wins_on_draw(&self, index: usize) -> u64 {
+        self.wins[index] - self.wins_on_play[index]
+    }
+    pub fn games_on_draw(&self, index: usize) -> u64 {
+        self.num_games - self.games_on_play[index]
+    }
+    /// Zero for a match of no games.
+    pub fn average_turns(&self) -> f64 {
+        if self.num_games == 0 {
+            return 0.0;
+        }
+        self.total_turns as f64 / self.num_games as f64
+    }
+}
+impl fmt::Display for MatchResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} v {}: {} games",
+            self.names[0], self.names[1], self.num_games
+        )?;
+        for index in 0..2 {
+            let (low, high) = self.confidence_interval(index);
+            writeln!(
+                f,
+                "  {} ({}): {} wins, {:.1}% [{:.1}%, {:.1}%], play {}/{}, draw {}/{}",
+                self.names[index],
+                index,
+                self.wins[index],
+                100.0 * self.win_rate(index),
+                100.0 * low,
+                100.0 * high,
+                self.wins_on_play[index],
+                self.games_on_play[index],
+                self.wins_on_draw(index),
+                self.games_on_draw(index),
+            )?;
+        }
+        write!(
+            f,
+            "  {} drawn, average end turn {:.1}, life {}, decking {}, turn limit {}, stalemate {}, forfeit {}",
+            self.draws,
+            self.average_turns(),
+            self.ended_by_life,
+            self.ended_by_decking,
+            self.ended_by_turn_limit,
+            self.ended_by_stalemate,
+            self.ended_by_forfeit
+        )?;
+        if let Some(forfeit) = &self.first_forfeit {
+            write!(
+                f,
+                "\n  First forfeit: {} in game {} (seed {}): {}",
+                self.names[forfeit.offender], forfeit.game_index, forfeit.seed, forfeit.action
+            )?;
+        }
+        Ok(())
+    }
+}
+
+struct GameRecord {
+    game_index: u64,
+    seed: u64,
+    first: usize,
+    winner: Option<usize>,
+    num_turn: u64,
+    reason: EndReason,
+}
+// Game `game_index` of a match, ready to play,
+// and the index of the entrant in the first seat.
+fn new_game(
+    player1: &Entrant,
+    player2: &Entrant,
+    config: &MatchConfig,
+    game_index: u64,
+) -> (GameState, usize) {
+    let seed = config.seed.wrapping_add(game_index);
+    let ((strategy1, deck1), (strategy2, deck2)) = (player1.build(), player2.build());
+    let (game, first) = match config.turn_order {
+        TurnOrder::Alternate => {
+            if game_index.is_multiple_of(2) {
+                let game =
+                    GameState::new(strategy1, deck1, strategy2, deck2, Printout::Nothing, seed);
+                (game, 0)
+            } else {
+                let game =
+                    GameState::new(strategy2, deck2, strategy1, deck1, Printout::Nothing, seed);
+                (game, 1)
+            }
+        }
+        TurnOrder::Fixed => {
+            let game = GameState::new(strategy1, deck1, strategy2, deck2, Printout::Nothing, seed);
+            (game, 0)
+        }
+        TurnOrder::Flip => {
+            let game = GameState::new_with_flip(
+                strategy1,
+                deck1,
+                strategy2,
+                deck2,
+                Printout::Nothing,
+                seed,
+            );
+            let first = if game.flipped() { 1 } else { 0 };
+            (game, first)
+        }
+    };
+    let game = game
+        .with_turn_limit(config.turn_limit)
+        .with_stalemate_turns(config.stalemate_turns);
+    (game, first)
+}
+fn play_game(
+    player1: &Entrant,
+    player2: &Entrant,
+    config: &MatchConfig,
+    game_index: u64,
+) -> GameRecord {
+    let (mut game, first) = new_game(player1, player2, config, game_index);
+    let outcome = game.play();
+    let winner = match outcome.winner {
+        Winner::Player1 => Some(first),
+        Winner::Player2 => Some(1 - first),
+        Winner::Draw => None,
+    };
+    GameRecord {
+        game_index,
+        seed: game.seed(),
+        first,
+        winner,
+        num_turn: game.num_turn(),
+        reason: outcome.reason,
+    }
+}
+
+/// Play game `game_index` of the match `play_match` would play with these
+/// arguments, with the same seed and seats, recording every decision
+/// so that `GameState::replay` can rebuild it.
+/// Returns the finished game and the index of the entrant in its first seat.
+pub fn replay_game(
+    player1: &Entrant,
+    player2: &Entrant,
+    config: &MatchConfig,
+    game_index: u64,
+) -> (GameState, usize) {
+    let (game, first) = new_game(player1, player2, config, game_index);
+    let mut game = game.with_decision_log();
+    game.play();
+    (game, first)
+}
+
+/// Play `config.num_games` games between fresh instances of two entrants,
+/// spread over `config.threads` worker threads.
+/// Each game's seed depends only on its index,
+/// so the result is the same for any number of threads.
+pub fn play_match(player1: &Entrant, player2: &Entrant, config: &MatchConfig) -> MatchResult {
+    let names = [player1.name(), player2.name()];
+    let threads = config.threads.max(1) as u64;
+    let partial_results: Vec<MatchResult> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads)
+            .map(|worker| {
+                let names = names.clone();
+                scope.spawn(move || {
+                    let mut partial = MatchResult::new(names);
+                    let mut game_index = worker;
+                    while game_index < config.num_games {
+                        partial.record(play_game(player1, player2, config, game_index));
+                        game_index += threads;
+                    }
+                    partial
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("Worker thread panicked"))
+            .collect()
+    });
+    let mut result = MatchResult::new(names);
+    for partial in &partial_results {
+        result.merge(partial);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_data::{MainPhaseAction, PlayerView};
+    use crate::player::{LandsSuck, MemnitesDontBlock};
+
+    // Plays the default deck like `LandsSuck`, but attacks with
+    // a creature it does not have when its hand holds four lands.
+    #[derive(Debug)]
+    struct Desperate;
+    impl Strategy for Desperate {
+        fn attack(&mut self, view: PlayerView) -> Vec<usize> {
+            if view.hand.iter().filter(|&card| card == &Card::Land).count() == 4 {
+                vec![view.creatures.len()]
+            } else {
+                LandsSuck.attack(view)
+            }
+        }
+        fn main_phase(&mut self, view: PlayerView) -> MainPhaseAction {
+            LandsSuck.main_phase(view)
+        }
+    }
+
+    #[test]
+    fn results_do_not_depend_on_threads() {
+        let desperate = || Box::new(Desperate) as Box<dyn Strategy>;
+        let memnites = || Box::new(MemnitesDontBlock) as Box<dyn Strategy>;
+        let (player1, player2) = (Entrant::new(&desperate), Entrant::new(&memnites));
+        let config = |threads| MatchConfig {
+            num_games: 40,
+            seed: 28,
+            turn_order: TurnOrder::Alternate,
+            threads,
+            turn_limit: None,
+            stalemate_turns: None,
+        };
+        let single = play_match(&player1, &player2, &config(1));
+        // Not in the first worker's games, so merging has to find it
+        let first_forfeit = single.first_forfeit.expect("Some games forfeited");
+        assert_eq!(first_forfeit.game_index, 5);
+        for threads in [2, 3, 8] {
+            assert_eq!(play_match(&player1, &player2, &config(threads)), single);
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use crate::runner::{play_match, Entrant, MatchConfig, MatchResult};
+use std::fmt;
+
+/// One row of the standings table.
+#[derive(Debug, Clone)]
+pub struct Standing {
+    pub name: String,
+    pub wins: u64,
+    pub games: u64,
+}
+impl Standing {
+    /// Zero before any games are played.
+    pub fn win_rate(&self) -> f64 {
+        if self.games == 0 {
+            return 0.0;
+        }
+        self.wins as f64 / self.games as f64
+    }
+}
+#[derive(Debug, Clone)]
+pub struct TournamentResult {
+    pub names: Vec<String>,
+    /// `matches[i][j]` is the match with entrant `i` as the first strategy
+    /// and entrant `j` as the second, mirror matches included.
+    pub matches: Vec<Vec<MatchResult>>,
+}
+impl TournamentResult {
+    /// Totals for each entrant over every match except its mirror,
+    /// best win rate first.
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = self
+            .names
+            .iter()
+            .map(|name| Standing {
+                name: name.clone(),
+                wins: 0,
+                games: 0,
+            })
+            .collect();
+        for (i, row) in self.matches.iter().enumerate() {
+            for (j, result) in row.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                standings[i].wins += result.wins[0];
+                standings[i].games += result.num_games;
+                standings[j].wins += result.wins[1];
+                standings[j].games += result.num_games;
+            }
+        }
+        standings.sort_by(|a, b| b.win_rate().total_cmp(&a.win_rate()));
+        standings
+    }
+}
+impl fmt::Display for TournamentResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let width = self.names.iter().map(|n| n.len()).max().unwrap_or(0);
+        writeln!(f, "Win rate of row entrant going first:")?;
+        write!(f, "   {:width$}", "", width = width)?;
+        for j in 0..self.names.len() {
+            write!(f, " {:>6}", j)?;
+        }
+        writeln!(f)?;
+        for (i, row) in self.matches.iter().enumerate() {
+            write!(f, "{:>2} {:width$}", i, self.names[i], width = width)?;
+            for result in row {
+                write!(f, " {:>5.1}%", 100.0 * result.win_rate_on_play(0))?;
+            }
+            writeln!(f)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "Standings:")?;
+        for (place, standing) in self.standings().iter().enumerate() {
+            writeln!(
+                f,
+                "{:>2} {:width$} {:>5.1}% ({}/{})",
+                place + 1,
+                standing.name,
+                100.0 * standing.win_rate(),
+                standing.wins,
+                standing.games,
+                width = width
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Play a match of `config.num_games` games for every ordered pairing
+/// of entrants, including each entrant against itself.
+/// Every game in the tournament gets a distinct seed.
+pub fn play_tournament(entrants: &[Entrant], config: &MatchConfig) -> TournamentResult {
+    let names = entrants.iter().map(|entrant| entrant.name()).collect();
+    let mut matches = vec![];
+    for (i, player1) in entrants.iter().enumerate() {
+        let mut row = vec![];
+        for (j, player2) in entrants.iter().enumerate() {
+            let pairing_index = (i * entrants.len() + j) as u64;
+            let pairing_config = MatchConfig {
+                seed: config
+                    .seed
+                    .wrapping_add(pairing_index.wrapping_mul(config.num_games)),
+                ..*config
+            };
+            row.push(play_match(player1, player2, &pairing_config));
+        }
+        matches.push(row);
+    }
+    TournamentResult { names, matches }
+}
@@ -0,0 +1,882 @@
+use crate::cards::Keyword;
+use crate::events::GameEvent;
+use crate::game::{
+    arrange_blockers, check_attackers, check_blocker_order, check_damage_assignment, check_discard,
+    check_main_phase_action, check_muligan, lethal_damage, GameState, PlayerState,
+};
+use crate::game_data::{
+    Card, Creature, DamageAssignment, MainPhaseAction, MuliganChoice, PlayerView,
+};
+use crate::player::Strategy;
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor};
+use crossterm::{cursor, execute, queue, terminal};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::process;
+
+const CARD_WIDTH: u16 = 12;
+const CARD_HEIGHT: u16 = 5;
+
+thread_local! {
+    // The game's events as text. The watcher keeps this up to date
+    // so that `TuiHuman` can show it too.
+    static LOG: RefCell<Vec<String>> = const { RefCell::new(vec![]) };
+    // How many lines the log is scrolled back from its end.
+    static SCROLL: Cell<usize> = const { Cell::new(0) };
+    // Set when the watcher is told to stop pausing.
+    static FAST_FORWARD: Cell<bool> = const { Cell::new(false) };
+    // The seat of the person playing on the board, if there is one.
+    static VIEWER: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// Switches the terminal to a full-screen raw mode until dropped.
+/// `Printout::Tui` and `TuiHuman` draw only while a session is active.
+pub struct Session {
+    _private: (),
+}
+impl Session {
+    /// `viewer` is the seat of a `TuiHuman`, if one is playing.
+    /// The watcher then keeps that seat at the bottom and hides
+    /// the other hand; otherwise it shows both hands, seat 0 at the bottom.
+    pub fn start(viewer: Option<usize>) -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        FAST_FORWARD.with(|fast_forward| fast_forward.set(false));
+        VIEWER.with(|seat| seat.set(viewer));
+        Ok(Session { _private: () })
+    }
+}
+impl Drop for Session {
+    fn drop(&mut self) {
+        // Nothing useful can be done if restoring the terminal fails.
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Zone {
+    Theirs,
+    Mine,
+    Hand,
+}
+enum Hand<'a> {
+    Shown(&'a [Card]),
+    Hidden(usize),
+}
+struct Side<'a> {
+    title: String,
+    life: i64,
+    library: usize,
+    lands: u64,
+    tapped_lands: u64,
+    creatures: &'a [Creature],
+    hand: Hand<'a>,
+}
+// Everything on screen apart from the log.
+struct Board<'a> {
+    heading: String,
+    num_turn: u64,
+    top: Side<'a>,
+    bottom: Side<'a>,
+    prompt: String,
+    keys: String,
+    error: Option<String>,
+    cursor: Option<(Zone, usize)>,
+    marks: HashMap<(Zone, usize), String>,
+}
+impl<'a> Board<'a> {
+    fn zone_len(&self, zone: Zone) -> usize {
+        match zone {
+            Zone::Theirs => self.top.creatures.len(),
+            Zone::Mine => self.bottom.creatures.len(),
+            Zone::Hand => match self.bottom.hand {
+                Hand::Shown(hand) => hand.len(),
+                Hand::Hidden(_) => 0,
+            },
+        }
+    }
+    fn cursor_in(&self, zone: Zone) -> Option<usize> {
+        match self.cursor {
+            Some((cursor_zone, index)) if cursor_zone == zone => Some(index),
+            _ => None,
+        }
+    }
+}
+
+fn player_side(state: &PlayerState, index: usize, is_current: bool, shown: bool) -> Side<'_> {
+    Side {
+        title: format!(
+            "{:?} ({}){}",
+            state.strategy(),
+            index,
+            if is_current { " <<<" } else { "" }
+        ),
+        life: state.life(),
+        library: state.deck_size(),
+        lands: state.num_lands(),
+        tapped_lands: state.tapped_lands(),
+        creatures: state.creatures(),
+        hand: if shown {
+            Hand::Shown(state.hand())
+        } else {
+            Hand::Hidden(state.hand().len())
+        },
+    }
+}
+fn view_board<'a>(view: PlayerView<'a>, heading: &str) -> Board<'a> {
+    Board {
+        heading: format!("Turn {}: {}", view.num_turn, heading),
+        num_turn: view.num_turn,
+        top: Side {
+            title: "Opponent".to_string(),
+            life: view.oth_life,
+            library: view.oth_deck_size,
+            lands: view.oth_lands,
+            tapped_lands: view.oth_tapped_lands,
+            creatures: view.oth_creatures,
+            hand: Hand::Hidden(view.oth_hand_size),
+        },
+        bottom: Side {
+            title: "You".to_string(),
+            life: view.life,
+            library: view.deck_size,
+            lands: view.num_lands,
+            tapped_lands: view.tapped_lands,
+            creatures: view.creatures,
+            hand: Hand::Shown(view.hand),
+        },
+        prompt: String::new(),
+        keys: String::new(),
+        error: None,
+        cursor: None,
+        marks: HashMap::new(),
+    }
+}
+
+// Cut `text` to `width` characters.
+fn fit(text: &str, width: u16) -> String {
+    text.chars().take(width as usize).collect()
+}
+fn print_at(out: &mut impl Write, x: u16, y: u16, text: &str, width: u16) -> io::Result<()> {
+    queue!(
+        out,
+        cursor::MoveTo(x, y),
+        Print(fit(text, width.saturating_sub(x)))
+    )
+}
+// What a card box shows.
+struct CardFace<'a> {
+    name: &'a str,
+    stats: String,
+    keywords: String,
+    tapped: bool,
+}
+fn draw_card(
+    out: &mut impl Write,
+    (x, y): (u16, u16),
+    index: usize,
+    face: &CardFace,
+    mark: Option<&String>,
+    under_cursor: bool,
+) -> io::Result<()> {
+    if under_cursor {
+        queue!(out, SetAttribute(Attribute::Reverse))?;
+    }
+    if mark.is_some() {
+        queue!(out, SetForegroundColor(Color::Yellow))?;
+    } else if face.tapped {
+        queue!(out, SetAttribute(Attribute::Dim))?;
+    }
+    let inner = (CARD_WIDTH - 2) as usize;
+    let lines = [
+        format!("┌{:─<inner$}┐", index, inner = inner),
+        format!("│{:<inner$}│", fit(face.name, inner as u16), inner = inner),
+        format!(
+            "│{:<inner$}│",
+            fit(&face.keywords, inner as u16),
+            inner = inner
+        ),
+        format!("│{:<inner$}│", face.stats, inner = inner),
+        format!(
+            "└{:─<inner$}┘",
+            fit(mark.map_or("", |m| m.as_str()), inner as u16),
+            inner = inner
+        ),
+    ];
+    for (row, line) in lines.iter().enumerate() {
+        queue!(out, cursor::MoveTo(x, y + row as u16), Print(line))?;
+    }
+    queue!(out, SetAttribute(Attribute::Reset), ResetColor)
+}
+fn draw_cards(
+    out: &mut impl Write,
+    y: u16,
+    width: u16,
+    board: &Board,
+    zone: Zone,
+    cards: &[CardFace],
+) -> io::Result<()> {
+    if cards.is_empty() {
+        return print_at(out, 2, y + 1, "(none)", width);
+    }
+    let capacity = ((width / CARD_WIDTH) as usize).max(2) - 1;
+    let cursor = board.cursor_in(zone);
+    let start = match cursor {
+        Some(index) if index >= capacity => index + 1 - capacity,
+        _ => 0,
+    };
+    for (slot, index) in (start..cards.len()).take(capacity).enumerate() {
+        draw_card(
+            out,
+            (slot as u16 * CARD_WIDTH, y),
+            index,
+            &cards[index],
+            board.marks.get(&(zone, index)),
+            cursor == Some(index),
+        )?;
+    }
+    let hidden = cards.len() - capacity.min(cards.len() - start);
+    if hidden > 0 {
+        let x = capacity as u16 * CARD_WIDTH;
+        print_at(out, x, y + 1, &format!("+{} more", hidden), width)?;
+    }
+    Ok(())
+}
+// Keywords abbreviated to fit a card box.
+fn keyword_codes(keywords: &[Keyword]) -> String {
+    let codes: Vec<&str> = keywords
+        .iter()
+        .map(|keyword| match keyword {
+            Keyword::Flying => "FLY",
+            Keyword::Reach => "RCH",
+            Keyword::Menace => "MEN",
+            Keyword::FirstStrike => "1ST",
+            Keyword::DoubleStrike => "DBL",
+            Keyword::Trample => "TRM",
+            Keyword::Defender => "DEF",
+            Keyword::Vigilance => "VIG",
+            Keyword::Haste => "HST",
+            Keyword::Deathtouch => "DTH",
+            Keyword::Lifelink => "LNK",
+            Keyword::Indestructible => "IND",
+        })
+        .collect();
+    codes.join(" ")
+}
+fn creature_cards(creatures: &[Creature], num_turn: u64) -> Vec<CardFace<'_>> {
+    creatures
+        .iter()
+        .map(|creature| CardFace {
+            name: creature.name(),
+            stats: format!(
+                "{}/{}{}",
+                creature.pow(),
+                creature.tou(),
+                if creature.tapped {
+                    " TAP"
+                } else if creature.summoning_sick(num_turn) {
+                    " SICK"
+                } else {
+                    ""
+                }
+            ),
+            keywords: keyword_codes(creature.card().keywords()),
+            tapped: creature.tapped,
+        })
+        .collect()
+}
+fn hand_cards(hand: &[Card]) -> Vec<CardFace<'_>> {
+    hand.iter()
+        .map(|card| match card {
+            Card::Land => CardFace {
+                name: "Land",
+                stats: String::new(),
+                keywords: String::new(),
+                tapped: false,
+            },
+            Card::Creature(cc) => CardFace {
+                name: cc.name(),
+                stats: format!("{}: {}/{}", cc.cmc(), cc.pow(), cc.tou()),
+                keywords: keyword_codes(cc.keywords()),
+                tapped: false,
+            },
+        })
+        .collect()
+}
+fn side_info(side: &Side) -> String {
+    let hand_size = match side.hand {
+        Hand::Shown(hand) => hand.len(),
+        Hand::Hidden(size) => size,
+    };
+    format!(
+        "{}   Life {}   Library {}   Hand {}   Lands {} ({} untapped)",
+        side.title,
+        side.life,
+        side.library,
+        hand_size,
+        side.lands,
+        side.lands - side.tapped_lands
+    )
+}
+fn draw(board: &Board) -> io::Result<()> {
+    let mut out = io::stdout();
+    let (width, height) = terminal::size()?;
+    queue!(out, terminal::Clear(terminal::ClearType::All))?;
+    queue!(out, SetAttribute(Attribute::Reverse))?;
+    print_at(
+        &mut out,
+        0,
+        0,
+        &format!("{:<1$}", board.heading, width as usize),
+        width,
+    )?;
+    queue!(out, SetAttribute(Attribute::Reset))?;
+    // Opponent, then a gap, then the player at the bottom
+    print_at(&mut out, 0, 1, &side_info(&board.top), width)?;
+    let top_hand = match board.top.hand {
+        Hand::Shown(hand) => {
+            let cards: Vec<String> = hand.iter().map(|card| card.to_string()).collect();
+            format!("Hand: {}", cards.join(", "))
+        }
+        Hand::Hidden(size) => format!("Hand: {} cards", size),
+    };
+    print_at(&mut out, 0, 2, &top_hand, width)?;
+    let mut y = 3;
+    draw_cards(
+        &mut out,
+        y,
+        width,
+        board,
+        Zone::Theirs,
+        &creature_cards(board.top.creatures, board.num_turn),
+    )?;
+    y += CARD_HEIGHT + 1;
+    draw_cards(
+        &mut out,
+        y,
+        width,
+        board,
+        Zone::Mine,
+        &creature_cards(board.bottom.creatures, board.num_turn),
+    )?;
+    y += CARD_HEIGHT;
+    print_at(&mut out, 0, y, &side_info(&board.bottom), width)?;
+    y += 1;
+    match board.bottom.hand {
+        Hand::Shown(hand) => {
+            draw_cards(&mut out, y, width, board, Zone::Hand, &hand_cards(hand))?;
+            y += CARD_HEIGHT;
+        }
+        Hand::Hidden(size) => {
+            print_at(&mut out, 0, y, &format!("Hand: {} cards", size), width)?;
+            y += 1;
+        }
+    }
+    // The log fills whatever is left above the footer
+    let footer_top = height.saturating_sub(3);
+    if y + 2 < footer_top {
+        queue!(out, SetAttribute(Attribute::Bold))?;
+        print_at(&mut out, 0, y, "Log", width)?;
+        queue!(out, SetAttribute(Attribute::Reset))?;
+        let log_height = (footer_top - y - 1) as usize;
+        LOG.with(|log| {
+            let log = log.borrow();
+            let scroll = SCROLL.with(|scroll| scroll.get()).min(log.len());
+            let end = log.len() - scroll;
+            let start = end.saturating_sub(log_height);
+            for (row, line) in log[start..end].iter().enumerate() {
+                print_at(&mut out, 1, y + 1 + row as u16, line, width)?;
+            }
+            Ok::<(), io::Error>(())
+        })?;
+    }
+    print_at(&mut out, 0, footer_top, &board.prompt, width)?;
+    if let Some(error) = &board.error {
+        queue!(out, SetForegroundColor(Color::Red))?;
+        print_at(&mut out, 0, footer_top + 1, error, width)?;
+        queue!(out, ResetColor)?;
+    }
+    queue!(out, SetAttribute(Attribute::Dim))?;
+    print_at(&mut out, 0, footer_top + 2, &board.keys, width)?;
+    queue!(out, SetAttribute(Attribute::Reset))?;
+    out.flush()
+}
+fn restore_and_exit() -> ! {
+    let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+    let _ = terminal::disable_raw_mode();
+    process::exit(130)
+}
+// The next key pressed, handling Ctrl-C and log scrolling itself.
+// Resizing returns `KeyCode::Null` so the caller redraws.
+fn read_key() -> KeyCode {
+    loop {
+        match event::read().expect("Terminal is readable") {
+            Event::Key(key) if key.kind != KeyEventKind::Release => {
+                if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+                    restore_and_exit();
+                }
+                match key.code {
+                    KeyCode::PageUp => SCROLL.with(|scroll| scroll.set(scroll.get() + 5)),
+                    KeyCode::PageDown => {
+                        SCROLL.with(|scroll| scroll.set(scroll.get().saturating_sub(5)))
+                    }
+                    code => return code,
+                }
+                return KeyCode::Null;
+            }
+            Event::Resize(_, _) => return KeyCode::Null,
+            _ => {}
+        }
+    }
+}
+// Draw the board and wait for a key, moving the cursor between
+// the items of `zones` with the arrow keys. Other keys are returned.
+fn next_key(board: &mut Board, zones: &[Zone]) -> KeyCode {
+    let usable: Vec<Zone> = zones
+        .iter()
+        .copied()
+        .filter(|&zone| board.zone_len(zone) > 0)
+        .collect();
+    let cursor_usable = matches!(board.cursor, Some((zone, _)) if usable.contains(&zone));
+    if !cursor_usable {
+        board.cursor = usable.first().map(|&zone| (zone, 0));
+    }
+    loop {
+        draw(board).expect("Terminal is writable");
+        let key = read_key();
+        let (zone, index) = match board.cursor {
+            Some(cursor) => cursor,
+            None => return key,
+        };
+        let position = usable.iter().position(|&z| z == zone).expect("Usable");
+        board.cursor = match key {
+            KeyCode::Left => Some((zone, index.saturating_sub(1))),
+            KeyCode::Right => Some((zone, (index + 1).min(board.zone_len(zone) - 1))),
+            KeyCode::Up if position > 0 => Some((usable[position - 1], 0)),
+            KeyCode::Down if position + 1 < usable.len() => Some((usable[position + 1], 0)),
+            KeyCode::Up | KeyCode::Down | KeyCode::Null => continue,
+            _ => return key,
+        };
+    }
+}
+fn toggle(selected: &mut Vec<usize>, index: usize) {
+    match selected.iter().position(|&i| i == index) {
+        Some(position) => {
+            selected.remove(position);
+        }
+        None => selected.push(index),
+    }
+}
+fn mark_all(board: &mut Board, zone: Zone, indices: &[usize], mark: &str) {
+    for &index in indices {
+        board.marks.insert((zone, index), mark.to_string());
+    }
+}
+const SELECT_KEYS: &str = "Arrows: move   Space: select   Enter: confirm   PgUp/PgDn: scroll log";
+
+fn main_phase_action(view: PlayerView, phase: &str) -> MainPhaseAction {
+    let mut board = view_board(view, phase);
+    board.prompt = "Choose a land to play or a creature to cast".to_string();
+    board.keys = "Arrows: move   Enter: play or cast   p: pass   PgUp/PgDn: scroll log".to_string();
+    loop {
+        match next_key(&mut board, &[Zone::Hand, Zone::Mine]) {
+            KeyCode::Char('p') => return MainPhaseAction::Pass,
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                let index = match board.cursor_in(Zone::Hand) {
+                    Some(index) => index,
+                    None => continue,
+                };
+                let main_phase_action = match view.hand[index] {
+                    Card::Land => MainPhaseAction::PlayLand(index),
+                    Card::Creature(_) => MainPhaseAction::Cast(index),
+                };
+                match check_main_phase_action(
+                    main_phase_action,
+                    view.hand,
+                    view.num_lands - view.tapped_lands,
+                    view.played_land,
+                ) {
+                    Ok(()) => return main_phase_action,
+                    Err(action) => board.error = Some(format!("Illegal: that {}", action)),
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// The event as text, without the cards in another player's hand
+// when someone is playing on the board.
+fn log_line(event: &GameEvent, viewer: Option<usize>) -> String {
+    let hidden = |player: usize| viewer.is_some() && viewer != Some(player);
+    match event {
+        GameEvent::Muligan { player, .. } if hidden(*player) => {
+            format!("Player {} muligans", player)
+        }
+        GameEvent::Keep { player, hand } if hidden(*player) => {
+            format!("Player {} keeps {} cards", player, hand.len())
+        }
+        GameEvent::Draw { player, .. } if hidden(*player) => {
+            format!("Player {} draws a card", player)
+        }
+        event => event.to_string(),
+    }
+}
+/// Show the game after a phase and wait for Enter,
+/// unless the watcher has been told to stop pausing.
+pub(crate) fn show_game(game: &GameState, phase: &str) {
+    let viewer = VIEWER.with(|seat| seat.get());
+    LOG.with(|log| {
+        *log.borrow_mut() = game
+            .events()
+            .iter()
+            .map(|event| log_line(event, viewer))
+            .collect()
+    });
+    SCROLL.with(|scroll| scroll.set(0));
+    let current = game.current_player_index();
+    let bottom = viewer.unwrap_or(0);
+    let side = |index: usize| {
+        let shown = viewer.is_none() || viewer == Some(index);
+        player_side(game.player_state(index), index, current == index, shown)
+    };
+    let mut board = Board {
+        heading: format!("Turn {}: {}", game.num_turn(), phase),
+        num_turn: game.num_turn(),
+        top: side(1 - bottom),
+        bottom: side(bottom),
+        prompt: String::new(),
+        keys: "Enter: continue   q: stop pausing   PgUp/PgDn: scroll log   Ctrl-C: quit"
+            .to_string(),
+        error: None,
+        cursor: None,
+        marks: HashMap::new(),
+    };
+    if FAST_FORWARD.with(|fast_forward| fast_forward.get()) {
+        board.keys.clear();
+        draw(&board).expect("Terminal is writable");
+        return;
+    }
+    loop {
+        match next_key(&mut board, &[]) {
+            KeyCode::Enter | KeyCode::Char(' ') => return,
+            KeyCode::Char('q') => {
+                FAST_FORWARD.with(|fast_forward| fast_forward.set(true));
+                return;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A person choosing with the keyboard on the full-screen board.
+/// Needs an active `Session`.
+#[derive(Debug)]
+pub struct TuiHuman;
+impl Strategy for TuiHuman {
+    fn muligan_choice(&mut self, hand: &[Card], num_muls: usize, is_first: bool) -> MuliganChoice {
+        let mut board = Board {
+            heading: format!(
+                "Opening hand {}, after {} muligans",
+                if is_first {
+                    "on the play"
+                } else {
+                    "on the draw"
+                },
+                num_muls
+            ),
+            num_turn: 0,
+            top: Side {
+                title: "Opponent".to_string(),
+                life: 20,
+                library: 0,
+                lands: 0,
+                tapped_lands: 0,
+                creatures: &[],
+                hand: Hand::Hidden(0),
+            },
+            bottom: Side {
+                title: "You".to_string(),
+                life: 20,
+                library: 0,
+                lands: 0,
+                tapped_lands: 0,
+                creatures: &[],
+                hand: Hand::Shown(hand),
+            },
+            prompt: "Keep this hand?".to_string(),
+            keys: "k: keep   m: muligan".to_string(),
+            error: None,
+            cursor: None,
+            marks: HashMap::new(),
+        };
+        loop {
+            match next_key(&mut board, &[]) {
+                KeyCode::Char('k') => break,
+                KeyCode::Char('m') => return MuliganChoice::Muligan,
+                _ => {}
+            }
+        }
+        if num_muls == 0 {
+            return MuliganChoice::KeepExcept(vec![]);
+        }
+        board.prompt = format!("Choose {} cards to put on the bottom", num_muls);
+        board.keys = SELECT_KEYS.to_string();
+        let mut selected = vec![];
+        loop {
+            board.marks.clear();
+            mark_all(&mut board, Zone::Hand, &selected, "BOTTOM");
+            match next_key(&mut board, &[Zone::Hand]) {
+                KeyCode::Char(' ') => {
+                    if let Some(index) = board.cursor_in(Zone::Hand) {
+                        toggle(&mut selected, index);
+                    }
+                }
+                KeyCode::Enter => match check_muligan(&selected, num_muls, hand.len()) {
+                    Ok(()) => return MuliganChoice::KeepExcept(selected),
+                    Err(action) => board.error = Some(format!("Illegal: that {}", action)),
+                },
+                _ => {}
+            }
+        }
+    }
+    fn attack(&mut self, view: PlayerView) -> Vec<usize> {
+        if !(0..view.creatures.len()).any(|i| view.can_attack(i)) {
+            return vec![];
+        }
+        let mut board = view_board(view, "declare attackers");
+        board.prompt = "Choose attackers".to_string();
+        board.keys = SELECT_KEYS.to_string();
+        let mut selected = vec![];
+        loop {
+            board.marks.clear();
+            mark_all(&mut board, Zone::Mine, &selected, "ATTACK");
+            match next_key(&mut board, &[Zone::Mine, Zone::Hand]) {
+                KeyCode::Char(' ') => {
+                    if let Some(index) = board.cursor_in(Zone::Mine) {
+                        toggle(&mut selected, index);
+                    }
+                }
+                KeyCode::Enter => match check_attackers(&selected, view.creatures, view.num_turn) {
+                    Ok(()) => return selected,
+                    Err(action) => board.error = Some(format!("Illegal: that {}", action)),
+                },
+                _ => {}
+            }
+        }
+    }
+    fn block(&mut self, view: PlayerView, attackers: &[usize]) -> Vec<(usize, usize)> {
+        if attackers.is_empty() || view.creatures.iter().all(|c| c.tapped) {
+            return vec![];
+        }
+        let mut board = view_board(view, "declare blockers");
+        board.prompt = "Space cycles which attacker the selected creature blocks".to_string();
+        board.keys = SELECT_KEYS.to_string();
+        // Blocker to position in `attackers`
+        let mut blocks: HashMap<usize, usize> = HashMap::new();
+        loop {
+            board.marks.clear();
+            mark_all(&mut board, Zone::Theirs, attackers, "ATTACKING");
+            for (&blocker, &position) in &blocks {
+                board.marks.insert(
+                    (Zone::Mine, blocker),
+                    format!("BLOCKS {}", attackers[position]),
+                );
+            }
+            match next_key(&mut board, &[Zone::Mine, Zone::Theirs]) {
+                KeyCode::Char(' ') => {
+                    if let Some(blocker) = board.cursor_in(Zone::Mine) {
+                        match blocks.get(&blocker) {
+                            Some(&position) if position + 1 < attackers.len() => {
+                                blocks.insert(blocker, position + 1);
+                            }
+                            Some(_) => {
+                                blocks.remove(&blocker);
+                            }
+                            None => {
+                                blocks.insert(blocker, 0);
+                            }
+                        }
+                    }
+                }
+                KeyCode::Enter => {
+                    let mut pairs: Vec<(usize, usize)> = blocks
+                        .iter()
+                        .map(|(&blocker, &position)| (blocker, attackers[position]))
+                        .collect();
+                    pairs.sort_unstable();
+                    match arrange_blockers(&pairs, attackers, view.oth_creatures, view.creatures) {
+                        Ok(_) => return pairs,
+                        Err(action) => board.error = Some(format!("Illegal: that {}", action)),
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    fn order_blockers(
+        &mut self,
+        view: PlayerView,
+        default_ordering: &HashMap<usize, Vec<usize>>,
+    ) -> HashMap<usize, Vec<usize>> {
+        let mut ordering = default_ordering.clone();
+        let mut attackers: Vec<usize> = default_ordering
+            .iter()
+            .filter(|(_, blockers)| blockers.len() > 1)
+            .map(|(&attacker, _)| attacker)
+            .collect();
+        attackers.sort_unstable();
+        for attacker in attackers {
+            let blockers = &default_ordering[&attacker];
+            let mut board = view_board(view, "order blockers");
+            board.prompt = format!(
+                "Select the blockers of your {} in the order it deals damage",
+                view.creatures[attacker].name()
+            );
+            board.keys = SELECT_KEYS.to_string();
+            let expected: HashMap<usize, Vec<usize>> =
+                vec![(attacker, blockers.clone())].into_iter().collect();
+            let mut order = vec![];
+            loop {
+                board.marks.clear();
+                board
+                    .marks
+                    .insert((Zone::Mine, attacker), "ORDERING".to_string());
+                mark_all(&mut board, Zone::Theirs, blockers, "BLOCKING");
+                for (position, &blocker) in order.iter().enumerate() {
+                    board
+                        .marks
+                        .insert((Zone::Theirs, blocker), format!("#{}", position + 1));
+                }
+                match next_key(&mut board, &[Zone::Theirs, Zone::Mine]) {
+                    KeyCode::Char(' ') => {
+                        if let Some(index) = board.cursor_in(Zone::Theirs) {
+                            toggle(&mut order, index);
+                        }
+                    }
+                    KeyCode::Enter => {
+                        let given = vec![(attacker, order.clone())].into_iter().collect();
+                        match check_blocker_order(&expected, &given) {
+                            Ok(()) => break,
+                            Err(action) => board.error = Some(format!("Illegal: that {}", action)),
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            ordering.insert(attacker, order);
+        }
+        ordering
+    }
+    fn assign_damage(
+        &mut self,
+        view: PlayerView,
+        attacker: usize,
+        blockers: &[usize],
+        default_assignment: &DamageAssignment,
+    ) -> DamageAssignment {
+        let creature = &view.creatures[attacker];
+        let trample = creature.has(Keyword::Trample);
+        let lethal: Vec<u64> = blockers
+            .iter()
+            .map(|&blocker| lethal_damage(creature, &view.oth_creatures[blocker]))
+            .collect();
+        let mut board = view_board(view, "assign combat damage");
+        board.keys = if trample {
+            "Arrows: move   +/-: blocker damage   [/]: player damage   d: default   Enter: confirm"
+        } else {
+            "Arrows: move   +/-: blocker damage   d: default   Enter: confirm"
+        }
+        .to_string();
+        let mut assignment = default_assignment.clone();
+        loop {
+            let total: u64 = assignment.blockers.iter().sum::<u64>() + assignment.player;
+            board.prompt = format!(
+                "Split {} damage from your {}: {} assigned{}",
+                creature.pow(),
+                creature.name(),
+                total,
+                if trample {
+                    format!(", {} to the player", assignment.player)
+                } else {
+                    String::new()
+                }
+            );
+            board.marks.clear();
+            board
+                .marks
+                .insert((Zone::Mine, attacker), "ASSIGNING".to_string());
+            for (position, &blocker) in blockers.iter().enumerate() {
+                board.marks.insert(
+                    (Zone::Theirs, blocker),
+                    format!("{} OF {}", assignment.blockers[position], lethal[position]),
+                );
+            }
+            let selected = board
+                .cursor_in(Zone::Theirs)
+                .and_then(|index| blockers.iter().position(|&blocker| blocker == index));
+            match next_key(&mut board, &[Zone::Theirs, Zone::Mine]) {
+                KeyCode::Char('+') | KeyCode::Char('=') => {
+                    if let Some(position) = selected {
+                        assignment.blockers[position] += 1;
+                    }
+                }
+                KeyCode::Char('-') => {
+                    if let Some(position) = selected {
+                        assignment.blockers[position] =
+                            assignment.blockers[position].saturating_sub(1);
+                    }
+                }
+                KeyCode::Char(']') if trample => assignment.player += 1,
+                KeyCode::Char('[') if trample => {
+                    assignment.player = assignment.player.saturating_sub(1)
+                }
+                KeyCode::Char('d') => assignment = default_assignment.clone(),
+                KeyCode::Enter => {
+                    match check_damage_assignment(
+                        &assignment,
+                        attacker,
+                        creature.pow(),
+                        trample,
+                        &lethal,
+                    ) {
+                        Ok(()) => return assignment,
+                        Err(action) => board.error = Some(format!("Illegal: that {}", action)),
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    fn precombat_main_phase(&mut self, view: PlayerView) -> MainPhaseAction {
+        main_phase_action(view, "precombat main phase")
+    }
+    fn main_phase(&mut self, view: PlayerView) -> MainPhaseAction {
+        main_phase_action(view, "postcombat main phase")
+    }
+    fn discard(&mut self, view: PlayerView) -> Vec<usize> {
+        let mut board = view_board(view, "discard");
+        board.prompt = format!("Choose {} cards to discard", view.hand.len() - 7);
+        board.keys = SELECT_KEYS.to_string();
+        let mut selected = vec![];
+        loop {
+            board.marks.clear();
+            mark_all(&mut board, Zone::Hand, &selected, "DISCARD");
+            match next_key(&mut board, &[Zone::Hand]) {
+                KeyCode::Char(' ') => {
+                    if let Some(index) = board.cursor_in(Zone::Hand) {
+                        toggle(&mut selected, index);
+                    }
+                }
+                KeyCode::Enter => match check_discard(&selected, view.hand.len()) {
+                    Ok(()) => return selected,
+                    Err(action) => board.error = Some(format!("Illegal: that {}", action)),
+                },
+                _ => {}
+            }
+        }
+    }
+}